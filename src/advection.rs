@@ -0,0 +1,95 @@
+// Explicit schemes for the 1D conservation law u_t + f(u)_x = 0 of x1-x3, with the
+// wave speed a(u) = f'(u) > 0. The first node is an inflow boundary held fixed; nodes
+// whose stencil leaves the domain fall back to first-order upwinding.
+use ndarray::prelude::*;
+
+use crate::tvd::{self, Limiter};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scheme {
+    // Backward difference of the lessons, in advective form
+    Upwind,
+    LaxFriedrichs,
+    // Richtmyer two-step form
+    LaxWendroff,
+    MacCormack,
+    BeamWarming,
+    // Finite-volume MUSCL with a slope limiter, from `tvd`
+    Muscl(Limiter),
+}
+
+// Linear convection: f = c u
+pub fn linear(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, c: f64, scheme: Scheme) {
+    for _ in 0..nt {
+        step(u, dx, dt, scheme, |u| c * u, |_| c);
+    }
+}
+
+// Inviscid Burgers: f = u^2 / 2
+pub fn burgers(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, scheme: Scheme) {
+    for _ in 0..nt {
+        step(u, dx, dt, scheme, |u| u.powi(2) / 2., |u| u);
+    }
+}
+
+pub fn step<F, A>(u: &mut Array1<f64>, dx: f64, dt: f64, scheme: Scheme, f: F, a: A)
+where
+    F: Fn(f64) -> f64,
+    A: Fn(f64) -> f64,
+{
+    let nx = u.shape()[0];
+    let un = u.clone();
+    let flux = un.mapv(&f);
+    let r = dt / dx;
+    let upwind = |ith: usize| un[ith] - a(un[ith]) * dt / dx * (un[ith] - un[ith - 1]);
+
+    match scheme {
+        Scheme::Upwind => {
+            for ith in 1..nx {
+                u[ith] = upwind(ith);
+            }
+        }
+        Scheme::LaxFriedrichs => {
+            for ith in 1..nx - 1 {
+                u[ith] =
+                    (un[ith + 1] + un[ith - 1]) / 2. - r / 2. * (flux[ith + 1] - flux[ith - 1]);
+            }
+            u[nx - 1] = upwind(nx - 1);
+        }
+        Scheme::LaxWendroff => {
+            // Midpoint values at half a step, between nodes ith and ith + 1
+            let half = Array::from_shape_fn(nx - 1, |ith| {
+                (un[ith] + un[ith + 1]) / 2. - r / 2. * (flux[ith + 1] - flux[ith])
+            });
+            for ith in 1..nx - 1 {
+                u[ith] = un[ith] - r * (f(half[ith]) - f(half[ith - 1]));
+            }
+            u[nx - 1] = upwind(nx - 1);
+        }
+        Scheme::MacCormack => {
+            // Forward-difference predictor, backward-difference corrector
+            let mut predictor = un.clone();
+            for ith in 0..nx - 1 {
+                predictor[ith] = un[ith] - r * (flux[ith + 1] - flux[ith]);
+            }
+            let fp = predictor.mapv(&f);
+            for ith in 1..nx - 1 {
+                u[ith] = (un[ith] + predictor[ith]) / 2. - r / 2. * (fp[ith] - fp[ith - 1]);
+            }
+            u[nx - 1] = upwind(nx - 1);
+        }
+        Scheme::Muscl(limiter) => tvd::step(u, dx, dt, limiter, f, a),
+        Scheme::BeamWarming => {
+            u[1] = upwind(1);
+            for ith in 2..nx {
+                let (a1, a2) = (
+                    a((un[ith] + un[ith - 1]) / 2.),
+                    a((un[ith - 1] + un[ith - 2]) / 2.),
+                );
+                u[ith] = un[ith] - r / 2. * (3. * flux[ith] - 4. * flux[ith - 1] + flux[ith - 2])
+                    + r.powi(2) / 2.
+                        * (a1 * (flux[ith] - flux[ith - 1]) - a2 * (flux[ith - 1] - flux[ith - 2]));
+            }
+        }
+    }
+}
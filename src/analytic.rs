@@ -0,0 +1,103 @@
+// Closed-form solutions of the 1D lessons, for measuring the error of a numerical run
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+use crate::norm::{norms, Norms};
+
+// Errors at time `t` of a node solution with u[i] at x = i * dx
+pub fn errors<F>(u: &Array1<f64>, dx: f64, t: f64, exact: F) -> Norms
+where
+    F: Fn(f64, f64) -> f64,
+{
+    let e = Array::from_shape_fn(u.len(), |ith| u[ith] - exact(ith as f64 * dx, t));
+    return norms(&e);
+}
+
+// The hat of x1-x4: u = 2 on [0.5, 1] and u = 1 elsewhere
+pub fn hat(x: f64) -> f64 {
+    return if (0.5..=1.).contains(&x) { 2. } else { 1. };
+}
+
+// Linear convection of the hat (x1, x3)
+pub fn advected_hat(x: f64, t: f64, c: f64) -> f64 {
+    return hat(x - c * t);
+}
+
+// Inviscid Burgers of the hat (x2): a rarefaction fan behind, a shock in front.
+// Valid until the fan catches the shock at t = 1
+pub fn burgers_hat(x: f64, t: f64) -> f64 {
+    return if x < 0.5 + t {
+        1.
+    } else if x < 0.5 + 2. * t {
+        (x - 0.5) / t
+    } else if x < 1. + 1.5 * t {
+        2.
+    } else {
+        1.
+    };
+}
+
+// Diffusion of the hat on the real line (x4): the hat convolved with the heat kernel
+pub fn diffused_hat(x: f64, t: f64, nu: f64) -> f64 {
+    if t == 0. {
+        return hat(x);
+    }
+    let s = 2. * (nu * t).sqrt();
+    return 1. + 0.5 * (erf((x - 0.5) / s) - erf((x - 1.) / s));
+}
+
+// A smooth bump for convergence studies: 1 + exp(-((x - x0) / w)^2)
+pub fn gaussian(x: f64, x0: f64, w: f64) -> f64 {
+    return 1. + (-((x - x0) / w).powi(2)).exp();
+}
+
+// The bump diffused on the real line: its width grows as w^2 + 4 nu t
+pub fn diffused_gaussian(x: f64, t: f64, nu: f64, x0: f64, w: f64) -> f64 {
+    let s = w.powi(2) + 4. * nu * t;
+    return 1. + w / s.sqrt() * (-(x - x0).powi(2) / s).exp();
+}
+
+// The radial bump 1 + exp(-r^2 / w^2) about (x0, y0) diffused on the plane (x9)
+pub fn diffused_gaussian2(x: f64, y: f64, t: f64, nu: f64, x0: f64, y0: f64, w: f64) -> f64 {
+    let s = w.powi(2) + 4. * nu * t;
+    return 1. + w.powi(2) / s * (-((x - x0).powi(2) + (y - y0).powi(2)) / s).exp();
+}
+
+// Viscous Burgers with the saw-tooth IC on [0, 2 pi] (x5), by the Cole-Hopf transform
+pub fn burgers(x: f64, t: f64, nu: f64) -> f64 {
+    return -2.
+        * nu
+        * (-(-8. * t + 2. * x) * (-(-4. * t + x).powi(2) / (4. * nu * (t + 1.))).exp()
+            / (4. * nu * (t + 1.))
+            - (-8. * t + 2. * x - 4. * PI)
+                * (-(-4. * t + x - 2. * PI).powi(2) / (4. * nu * (t + 1.))).exp()
+                / (4. * nu * (t + 1.)))
+        / ((-(-4. * t + x - 2. * PI).powi(2) / (4. * nu * (t + 1.))).exp()
+            + (-(-4. * t + x).powi(2) / (4. * nu * (t + 1.))).exp())
+        + 4.;
+}
+
+// Maclaurin series near the origin, Lentz's continued fraction for erfc in the tails
+pub fn erf(x: f64) -> f64 {
+    if x < 0. {
+        return -erf(-x);
+    }
+    if x < 3. {
+        let (mut term, mut sum) = (x, x);
+        let mut n = 0.;
+        while term.abs() > 1e-17 * sum.abs() {
+            n += 1.;
+            term *= -x.powi(2) / n;
+            sum += term / (2. * n + 1.);
+        }
+        return 2. / PI.sqrt() * sum;
+    }
+    if x > 6. {
+        return 1.;
+    }
+    let mut fraction = x;
+    for k in (1..=60).rev() {
+        fraction = x + k as f64 / 2. / fraction;
+    }
+    return 1. - (-x.powi(2)).exp() / PI.sqrt() / fraction;
+}
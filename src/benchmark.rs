@@ -0,0 +1,107 @@
+use ndarray::prelude::*;
+
+use crate::lesson::xe;
+use crate::norm::{norms, Norms};
+
+// Ghia, Ghia & Shin (1982), Table I: u along the vertical centreline of the unit cavity
+pub const GHIA_Y: [f64; 17] = [
+    1.0000, 0.9766, 0.9688, 0.9609, 0.9531, 0.8516, 0.7344, 0.6172, 0.5000, 0.4531, 0.2813, 0.1719,
+    0.1016, 0.0703, 0.0625, 0.0547, 0.0000,
+];
+pub const GHIA_U: [(usize, [f64; 17]); 3] = [
+    (
+        100,
+        [
+            1.00000, 0.84123, 0.78871, 0.73722, 0.68717, 0.23151, 0.00332, -0.13641, -0.20581,
+            -0.21090, -0.15662, -0.10150, -0.06434, -0.04775, -0.04192, -0.03717, 0.00000,
+        ],
+    ),
+    (
+        400,
+        [
+            1.00000, 0.75837, 0.68439, 0.61756, 0.55892, 0.29093, 0.16256, 0.02135, -0.11477,
+            -0.17119, -0.32726, -0.24299, -0.14612, -0.10338, -0.09266, -0.08186, 0.00000,
+        ],
+    ),
+    (
+        1000,
+        [
+            1.00000, 0.65928, 0.57492, 0.51117, 0.46604, 0.33304, 0.18719, 0.05702, -0.06080,
+            -0.10648, -0.27805, -0.38289, -0.29730, -0.22220, -0.20196, -0.18109, 0.00000,
+        ],
+    ),
+];
+
+// Table II: v along the horizontal centreline
+pub const GHIA_X: [f64; 17] = [
+    1.0000, 0.9688, 0.9609, 0.9531, 0.9453, 0.9063, 0.8594, 0.8047, 0.5000, 0.2344, 0.2266, 0.1563,
+    0.0938, 0.0781, 0.0703, 0.0625, 0.0000,
+];
+pub const GHIA_V: [(usize, [f64; 17]); 3] = [
+    (
+        100,
+        [
+            0.00000, -0.05906, -0.07391, -0.08864, -0.10313, -0.16914, -0.22445, -0.24533, 0.05454,
+            0.17527, 0.17507, 0.16077, 0.12317, 0.10890, 0.10091, 0.09233, 0.00000,
+        ],
+    ),
+    (
+        400,
+        [
+            0.00000, -0.12146, -0.15663, -0.19254, -0.22847, -0.23827, -0.44993, -0.38598, 0.05186,
+            0.30174, 0.30203, 0.28124, 0.22965, 0.20920, 0.19713, 0.18360, 0.00000,
+        ],
+    ),
+    (
+        1000,
+        [
+            0.00000, -0.21388, -0.27669, -0.33714, -0.39188, -0.51550, -0.42665, -0.31966, 0.02526,
+            0.32235, 0.33075, 0.37095, 0.32627, 0.30353, 0.29012, 0.27485, 0.00000,
+        ],
+    ),
+];
+
+pub fn ghia(re: usize) -> Option<([f64; 17], [f64; 17])> {
+    let u = GHIA_U.iter().find(|(r, _)| *r == re)?.1;
+    let v = GHIA_V.iter().find(|(r, _)| *r == re)?.1;
+    return Some((u, v));
+}
+
+// Viscosity for Re = U L / nu on the 2 x 2 cavity of the lessons under a unit lid
+pub fn cavity_nu(re: usize) -> f64 {
+    return 2. / re as f64;
+}
+
+// xe marched with dt = 0.01 to the steady state of that cavity at Reynolds number `re` on
+// the default grid of the lesson; returns u, v, p, dx and dy, or None when it does not
+// settle in 20000 steps
+pub fn xe_cavity(
+    re: usize,
+    options: &xe::Options,
+) -> Option<(Array2<f64>, Array2<f64>, Array2<f64>, f64, f64)> {
+    let (mut u, mut v, mut p, _, _, dx, dy, _, rho, _) = xe::default();
+    let nu = cavity_nu(re);
+    xe::steady(
+        &mut u, &mut v, &mut p, dx, dy, 0.01, rho, nu, 1e-3, 20_000, options,
+    )?;
+    return Some((u, v, p, dx, dy));
+}
+
+// Centreline errors of a unit-lid cavity run (lid along the last row) against Ghia et al.
+pub fn cavity(u: &Array2<f64>, v: &Array2<f64>, re: usize) -> Option<(Norms, Norms)> {
+    let (gu, gv) = ghia(re)?;
+    let eu = Array::from_shape_fn(17, |k| bilinear(u, 0.5, GHIA_Y[k]) - gu[k]);
+    let ev = Array::from_shape_fn(17, |k| bilinear(v, GHIA_X[k], 0.5) - gv[k]);
+    return Some((norms(&eu), norms(&ev)));
+}
+
+// Interpolates a node field at the fractional position (x, y) of its domain
+fn bilinear(a: &Array2<f64>, x: f64, y: f64) -> f64 {
+    let (ny, nx) = a.dim();
+    let (x, y) = (x * (nx - 1) as f64, y * (ny - 1) as f64);
+    let i = (x.floor() as usize).min(nx - 2);
+    let j = (y.floor() as usize).min(ny - 2);
+    let (tx, ty) = (x - i as f64, y - j as f64);
+    return (1. - ty) * ((1. - tx) * a[[j, i]] + tx * a[[j, i + 1]])
+        + ty * ((1. - tx) * a[[j + 1, i]] + tx * a[[j + 1, i + 1]]);
+}
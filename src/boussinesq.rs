@@ -0,0 +1,136 @@
+// Natural convection in the differentially heated square cavity of de Vahl Davis (1983):
+// the left wall is hot, the right wall cold and the others insulated. Lengths are scaled by
+// the side L, velocities by kappa / L and temperatures by the wall difference around their
+// mean, so that the xe cavity without a lid solves
+// u_t + (u.grad) u = -grad p + Pr lap(u) + Ra Pr T e_y, T_t + (u.grad) T = lap(T)
+// on the unit square with rho = 1, nu = Pr, kappa = 1 and g beta = Ra Pr.
+use ndarray::prelude::*;
+
+use crate::lesson::xe;
+use crate::macros::fill;
+use crate::poisson::PressureSolver;
+use crate::scalar::{self, Condition};
+use crate::upwind::Upwinding;
+
+// de Vahl Davis (1983), Table IV: Rayleigh number, mean Nusselt number, largest u along the
+// vertical centreline and largest v along the horizontal one
+pub const DE_VAHL_DAVIS: [(f64, f64, f64, f64); 4] = [
+    (1e3, 1.118, 3.649, 3.697),
+    (1e4, 2.243, 16.178, 19.617),
+    (1e5, 4.519, 34.73, 68.59),
+    (1e6, 8.800, 64.63, 219.36),
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    // The cavity solver, whose lid is held at rest
+    pub flow: xe::Options,
+    // Convection of both the momentum and the temperature
+    pub convection: Upwinding,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            flow: xe::Options {
+                pressure: PressureSolver::Spectral,
+                pressure_bc: xe::PressureBc::ZeroMean,
+                ..Default::default()
+            },
+            convection: Upwinding::SecondOrder,
+        };
+    }
+}
+
+// Fluid at rest at the mean temperature on n x n nodes: (u, v, p, t, dx)
+pub fn initial(n: usize) -> (Array2<f64>, Array2<f64>, Array2<f64>, Array2<f64>, f64) {
+    let dx = 1. / (n as f64 - 1.);
+    let mut t = Array::zeros((n, n));
+    fill!(t[.., 0] = 0.5);
+    fill!(t[.., -1] = -0.5);
+    let zeros = Array::zeros((n, n));
+    return (zeros.clone(), zeros.clone(), zeros, t, dx);
+}
+
+// Marches until the largest temperature change per unit time falls below `eps`; returns
+// the number of steps, or None when `max_steps` pass first or the march blows up
+pub fn steady(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    t: &mut Array2<f64>,
+    dx: f64,
+    dt: f64,
+    rayleigh: f64,
+    prandtl: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+) -> Option<usize> {
+    let walls = scalar::Options {
+        convection: options.convection,
+        left: Condition::Dirichlet(0.5),
+        right: Condition::Dirichlet(-0.5),
+        ..Default::default()
+    };
+    let flow = xe::Options {
+        convection: options.convection,
+        lid: 0.,
+        ..options.flow
+    };
+    // The change is measured over a batch of steps
+    let batch = 100;
+    let mut stepcount = 0;
+    while stepcount < max_steps {
+        let tn = t.clone();
+        let nt = batch.min(max_steps - stepcount);
+        xe::solve_boussinesq(
+            u,
+            v,
+            p,
+            t,
+            nt,
+            dx,
+            dx,
+            dt,
+            1.,
+            prandtl,
+            1.,
+            rayleigh * prandtl,
+            0.,
+            &flow,
+            &walls,
+        );
+        stepcount += nt;
+        let diff = (&*t - &tn).fold(0., |a: f64, x| a.max(x.abs())) / (nt as f64 * dt);
+        // max skips NaN, so a blow-up shows in the sums
+        if !(diff + t.sum() + u.sum() + v.sum()).is_finite() {
+            return None;
+        }
+        if diff <= eps {
+            return Some(stepcount);
+        }
+    }
+    return None;
+}
+
+// Mean Nusselt numbers -dT/dx of the hot and the cold wall, from second-order one-sided
+// differences integrated along the wall by the trapezoidal rule
+pub fn nusselt(t: &Array2<f64>, dx: f64, dy: f64) -> (f64, f64) {
+    let nx = t.ncols();
+    let hot = -(-3. * &t.column(0) + 4. * &t.column(1) - &t.column(2)) / (2. * dx);
+    let cold = -(3. * &t.column(nx - 1) - 4. * &t.column(nx - 2) + &t.column(nx - 3)) / (2. * dx);
+    let mean = |q: Array1<f64>| {
+        let ny = q.len();
+        return dy * (q.sum() - (q[0] + q[ny - 1]) / 2.) / ((ny - 1) as f64 * dy);
+    };
+    return (mean(hot), mean(cold));
+}
+
+// Largest u on the vertical centreline and largest v on the horizontal one; n must be odd
+pub fn maxima(u: &Array2<f64>, v: &Array2<f64>) -> (f64, f64) {
+    let (ny, nx) = u.dim();
+    let umax = u.column(nx / 2).fold(f64::NEG_INFINITY, |a, &x| a.max(x));
+    let vmax = v.row(ny / 2).fold(f64::NEG_INFINITY, |a, &x| a.max(x));
+    return (umax, vmax);
+}
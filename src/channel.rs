@@ -0,0 +1,57 @@
+// Wall and bulk quantities of the channel of xf: no-slip walls along the first and last
+// rows, y = 0 and y = H, and periodic in x with nodes dx apart. For the force f per unit
+// mass the laminar solution is the Poiseuille profile u = f / (2 nu) y (H - y), whose wall
+// shear stress is rho f H / 2, bulk velocity f H^2 / (12 nu) and skin friction 12 / Re.
+use ndarray::prelude::*;
+
+// Shear stress mu du/dn exerted by the flow on the walls along y = 0 and y = H, positive
+// along +x, from second-order one-sided differences
+pub fn wall_shear(u: &Array2<f64>, dy: f64, rho: f64, nu: f64) -> (Array1<f64>, Array1<f64>) {
+    let ny = u.nrows();
+    let mu = rho * nu;
+    let bottom = mu * (-3. * &u.row(0) + 4. * &u.row(1) - &u.row(2)) / (2. * dy);
+    let top = mu * (-3. * &u.row(ny - 1) + 4. * &u.row(ny - 2) - &u.row(ny - 3)) / (2. * dy);
+    return (bottom, top);
+}
+
+// Volume flow rate per unit depth through each section x = const, by the trapezoidal rule
+pub fn flow_rates(u: &Array2<f64>, dy: f64) -> Array1<f64> {
+    let ny = u.nrows();
+    return dy * (u.sum_axis(Axis(0)) - (&u.row(0) + &u.row(ny - 1)) / 2.);
+}
+
+// Flow rate averaged over the sections
+pub fn flow_rate(u: &Array2<f64>, dy: f64) -> f64 {
+    return flow_rates(u, dy).mean().unwrap();
+}
+
+pub fn bulk_velocity(u: &Array2<f64>, dy: f64) -> f64 {
+    return flow_rate(u, dy) / height(u, dy);
+}
+
+// tau_w / (rho ub^2 / 2), from the mean shear of both walls
+pub fn skin_friction(u: &Array2<f64>, dy: f64, rho: f64, nu: f64) -> f64 {
+    let (bottom, top) = wall_shear(u, dy, rho, nu);
+    let tau = (bottom.mean().unwrap() + top.mean().unwrap()) / 2.;
+    return tau / (rho * bulk_velocity(u, dy).powi(2) / 2.);
+}
+
+// Force per unit depth on both walls over one period nx dx, which balances the driving
+// force rho f H nx dx once the flow is steady
+pub fn drag(u: &Array2<f64>, dx: f64, dy: f64, rho: f64, nu: f64) -> f64 {
+    let (bottom, top) = wall_shear(u, dy, rho, nu);
+    return (bottom.sum() + top.sum()) * dx;
+}
+
+// u = f / (2 nu) y (H - y) on the nodes of u
+pub fn poiseuille(u: &Array2<f64>, dy: f64, nu: f64, f: f64) -> Array2<f64> {
+    let h = height(u, dy);
+    return Array::from_shape_fn(u.raw_dim(), |(j, _)| {
+        let y = j as f64 * dy;
+        f / (2. * nu) * y * (h - y)
+    });
+}
+
+fn height(u: &Array2<f64>, dy: f64) -> f64 {
+    return (u.nrows() - 1) as f64 * dy;
+}
@@ -0,0 +1,125 @@
+// Grid-convergence studies: errors on a sequence of nested grids and the observed order
+use ndarray::prelude::*;
+use ndarray::{Data, Slice};
+
+use crate::norm::{norms, Norms};
+
+// Time step tied to the spacing, dt = sigma h^power: a power of 1 holds a Courant number
+// fixed under refinement, a power of 2 a diffusion number
+#[derive(Clone, Copy, Debug)]
+pub struct TimeStep {
+    pub sigma: f64,
+    pub power: i32,
+}
+
+impl TimeStep {
+    pub fn dt(&self, h: f64) -> f64 {
+        return self.sigma * h.powi(self.power);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Study {
+    pub h: Vec<f64>,
+    pub errors: Vec<Norms>,
+}
+
+impl Study {
+    // Observed order between each pair of consecutive grids, in the chosen norm
+    pub fn orders<F>(&self, norm: F) -> Vec<f64>
+    where
+        F: Fn(&Norms) -> f64,
+    {
+        return (1..self.h.len())
+            .map(|ith| {
+                (norm(&self.errors[ith - 1]) / norm(&self.errors[ith])).ln()
+                    / (self.h[ith - 1] / self.h[ith]).ln()
+            })
+            .collect();
+    }
+
+    pub fn order<F>(&self, norm: F) -> f64
+    where
+        F: Fn(&Norms) -> f64,
+    {
+        return *self.orders(norm).last().unwrap();
+    }
+}
+
+// Node counts of `levels` nested grids, each `ratio` times finer than the last
+pub fn grids(n: usize, ratio: usize, levels: usize) -> Vec<usize> {
+    return (0..levels)
+        .map(|k| (n - 1) * ratio.pow(k as u32) + 1)
+        .collect();
+}
+
+// `run(n)` returns the spacing and the error norms against an exact solution
+pub fn exact<F>(grids: &[usize], run: F) -> Study
+where
+    F: Fn(usize) -> (f64, Norms),
+{
+    let (h, errors) = grids.iter().map(|&n| run(n)).unzip();
+    return Study { h, errors };
+}
+
+// `run(n)` returns the spacing and the solution; every grid is compared with the finest
+// one injected onto its nodes, so the finest grid itself does not enter the study
+pub fn finest<F, S, D>(grids: &[usize], run: F) -> Study
+where
+    F: Fn(usize) -> (f64, ArrayBase<S, D>),
+    S: Data<Elem = f64>,
+    D: Dimension,
+{
+    let n = grids[grids.len() - 1];
+    for &m in &grids[..grids.len() - 1] {
+        assert!(
+            m > 1 && (n - 1).is_multiple_of(m - 1),
+            "{} nodes are not nested in the finest grid of {}",
+            m,
+            n
+        );
+    }
+    let reference = run(n).1;
+    let (h, errors) = grids[..grids.len() - 1]
+        .iter()
+        .map(|&m| {
+            let (h, a) = run(m);
+            let stride = ((n - 1) / (m - 1)) as isize;
+            let r = reference.slice_each_axis(|_| Slice::new(0, None, stride));
+            (h, norms(&(&a - &r)))
+        })
+        .unzip();
+    return Study { h, errors };
+}
+
+// `exact` for a time-dependent problem on n nodes spanning `length`: `run(n, h, dt)`
+// returns the error norms with dt from `step`
+pub fn exact_in_time<F>(grids: &[usize], length: f64, step: TimeStep, run: F) -> Study
+where
+    F: Fn(usize, f64, f64) -> Norms,
+{
+    return exact(grids, |n| {
+        let h = length / (n - 1) as f64;
+        (h, run(n, h, step.dt(h)))
+    });
+}
+
+// `finest` for a time-dependent problem on n nodes spanning `length`: `run(n, h, dt)`
+// returns the solution with dt from `step`
+pub fn finest_in_time<F, S, D>(grids: &[usize], length: f64, step: TimeStep, run: F) -> Study
+where
+    F: Fn(usize, f64, f64) -> ArrayBase<S, D>,
+    S: Data<Elem = f64>,
+    D: Dimension,
+{
+    return finest(grids, |n| {
+        let h = length / (n - 1) as f64;
+        (h, run(n, h, step.dt(h)))
+    });
+}
+
+// Observed order and extrapolated value of a scalar computed on three grids
+pub fn richardson(coarse: f64, medium: f64, fine: f64, ratio: f64) -> (f64, f64) {
+    let p = ((coarse - medium) / (medium - fine)).abs().ln() / ratio.ln();
+    return (p, fine + (fine - medium) / (ratio.powf(p) - 1.));
+}
@@ -0,0 +1,198 @@
+// Implicit diffusion u_t = nu lap(u) with the boundary nodes held fixed. 1D steps solve one
+// tridiagonal system by the Thomas algorithm; 2D steps split the Laplacian into its x and y
+// parts (ADI), so each half solves a tridiagonal system per row or per column. Both are
+// unconditionally stable in nu dt / dx^2.
+use ndarray::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    // First order in time; Douglas-Rachford splitting in 2D
+    BackwardEuler,
+    // Second order in time; Peaceman-Rachford splitting in 2D
+    CrankNicolson,
+}
+
+impl Method {
+    // Weight of the new time level in the Laplacian
+    pub fn theta(self) -> f64 {
+        return match self {
+            Method::BackwardEuler => 1.,
+            Method::CrankNicolson => 0.5,
+        };
+    }
+}
+
+// Treatment of the viscous terms of the Navier-Stokes lessons
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Viscous {
+    Explicit,
+    // Implicit viscosity with explicit convection (IMEX), factored by ADI
+    Implicit(Method),
+}
+
+// Solves a[i] x[i - 1] + b[i] x[i] + c[i] x[i + 1] = d[i], ignoring a[0] and c[n - 1]
+pub fn thomas(a: &Array1<f64>, b: &Array1<f64>, c: &Array1<f64>, d: &Array1<f64>) -> Array1<f64> {
+    let n = d.len();
+    let (mut cp, mut dp) = (Array::zeros(n), Array::zeros(n));
+    cp[0] = c[0] / b[0];
+    dp[0] = d[0] / b[0];
+    for ith in 1..n {
+        let m = b[ith] - a[ith] * cp[ith - 1];
+        cp[ith] = c[ith] / m;
+        dp[ith] = (d[ith] - a[ith] * dp[ith - 1]) / m;
+    }
+    let mut x = Array::zeros(n);
+    x[n - 1] = dp[n - 1];
+    for ith in (0..n - 1).rev() {
+        x[ith] = dp[ith] - cp[ith] * x[ith + 1];
+    }
+    return x;
+}
+
+pub fn diffusion(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, nu: f64, method: Method) {
+    let theta = method.theta();
+    let r = nu * dt / dx.powi(2);
+    for _ in 0..nt {
+        let explicit = second_difference(u.view());
+        let d = &*u + &((1. - theta) * r * explicit);
+        u.assign(&solve_line(d.view(), theta * r));
+    }
+}
+
+pub fn diffusion2(
+    u: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    method: Method,
+) {
+    let (rx, ry) = (nu * dt / dx.powi(2), nu * dt / dy.powi(2));
+    for _ in 0..nt {
+        let yy = y_difference(u);
+        let half = match method {
+            // (1 - rx dxx) u* = (1 + ry dyy) u
+            Method::BackwardEuler => x_sweep(&(&*u + &(ry * &yy)), rx),
+            // (1 - rx/2 dxx) u* = (1 + ry/2 dyy) u
+            Method::CrankNicolson => x_sweep(&(&*u + &(ry / 2. * &yy)), rx / 2.),
+        };
+        let next = match method {
+            // (1 - ry dyy) u' = u* - ry dyy u
+            Method::BackwardEuler => y_sweep(&(&half - &(ry * &yy)), ry),
+            // (1 - ry/2 dyy) u' = (1 + rx/2 dxx) u*
+            Method::CrankNicolson => y_sweep(&(&half + &(rx / 2. * &x_difference(&half))), ry / 2.),
+        };
+        u.assign(&next);
+    }
+}
+
+// Solves (1 - rx dxx)(1 - ry dyy) u = d, with u = d on the edges other than those wrapped
+// around by a domain periodic in x
+pub fn helmholtz(d: &Array2<f64>, rx: f64, ry: f64, periodic: bool) -> Array2<f64> {
+    let mut w = d.clone();
+    for j in 1..d.nrows() - 1 {
+        let row = if periodic {
+            solve_cyclic(d.row(j), rx)
+        } else {
+            solve_line(d.row(j), rx)
+        };
+        w.row_mut(j).assign(&row);
+    }
+    if periodic {
+        let mut u = w.clone();
+        for i in 0..d.ncols() {
+            u.column_mut(i).assign(&solve_line(w.column(i), ry));
+        }
+        return u;
+    }
+    return y_sweep(&w, ry);
+}
+
+// Thomas algorithm for a periodic system, where a[0] couples x[0] to x[n - 1] and c[n - 1]
+// couples x[n - 1] to x[0], by the Sherman-Morrison formula
+pub fn cyclic(a: &Array1<f64>, b: &Array1<f64>, c: &Array1<f64>, d: &Array1<f64>) -> Array1<f64> {
+    let n = d.len();
+    let gamma = -b[0];
+    let mut bb = b.clone();
+    bb[0] -= gamma;
+    bb[n - 1] -= c[n - 1] * a[0] / gamma;
+    let x = thomas(a, &bb, c, d);
+    let mut v = Array::zeros(n);
+    v[0] = gamma;
+    v[n - 1] = c[n - 1];
+    let z = thomas(a, &bb, c, &v);
+    let factor = (x[0] + a[0] * x[n - 1] / gamma) / (1. + z[0] + a[0] * z[n - 1] / gamma);
+    return x - factor * z;
+}
+
+// u[i + 1] - 2 u[i] + u[i - 1] at the interior nodes, zero at the ends
+fn second_difference(u: ArrayView1<f64>) -> Array1<f64> {
+    let n = u.len();
+    return Array::from_shape_fn(n, |ith| {
+        if ith == 0 || ith == n - 1 {
+            return 0.;
+        }
+        return u[ith + 1] - 2. * u[ith] + u[ith - 1];
+    });
+}
+
+// Solves (1 - r d2) x = d on the interior, with x = d at the ends
+fn solve_line(d: ArrayView1<f64>, r: f64) -> Array1<f64> {
+    let n = d.len();
+    let mut a = Array::from_elem(n, -r);
+    let mut b = Array::from_elem(n, 1. + 2. * r);
+    let mut c = Array::from_elem(n, -r);
+    (a[0], b[0], c[0]) = (0., 1., 0.);
+    (a[n - 1], b[n - 1], c[n - 1]) = (0., 1., 0.);
+    return thomas(&a, &b, &c, &d.to_owned());
+}
+
+// Solves (1 - r d2) x = d with x periodic over the line
+fn solve_cyclic(d: ArrayView1<f64>, r: f64) -> Array1<f64> {
+    let n = d.len();
+    let (a, b, c) = (
+        Array::from_elem(n, -r),
+        Array::from_elem(n, 1. + 2. * r),
+        Array::from_elem(n, -r),
+    );
+    return cyclic(&a, &b, &c, &d.to_owned());
+}
+
+fn x_difference(u: &Array2<f64>) -> Array2<f64> {
+    let mut d = Array::zeros(u.raw_dim());
+    for (j, row) in u.rows().into_iter().enumerate() {
+        d.row_mut(j).assign(&second_difference(row));
+    }
+    d.row_mut(0).fill(0.);
+    d.row_mut(u.nrows() - 1).fill(0.);
+    return d;
+}
+
+fn y_difference(u: &Array2<f64>) -> Array2<f64> {
+    let mut d = Array::zeros(u.raw_dim());
+    for (i, column) in u.columns().into_iter().enumerate() {
+        d.column_mut(i).assign(&second_difference(column));
+    }
+    d.column_mut(0).fill(0.);
+    d.column_mut(u.ncols() - 1).fill(0.);
+    return d;
+}
+
+// Implicit in x along every interior row; the edge rows are copied
+fn x_sweep(d: &Array2<f64>, r: f64) -> Array2<f64> {
+    let mut u = d.clone();
+    for j in 1..d.nrows() - 1 {
+        u.row_mut(j).assign(&solve_line(d.row(j), r));
+    }
+    return u;
+}
+
+// Implicit in y along every interior column; the edge columns are copied
+fn y_sweep(d: &Array2<f64>, r: f64) -> Array2<f64> {
+    let mut u = d.clone();
+    for i in 1..d.ncols() - 1 {
+        u.column_mut(i).assign(&solve_line(d.column(i), r));
+    }
+    return u;
+}
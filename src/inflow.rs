@@ -0,0 +1,205 @@
+// Open channel on the MAC grid: a prescribed profile enters through the face x = 0, the
+// flow leaves through x = L = nx dx, and the walls y = 0 and y = H = ny dy are no-slip.
+// Unlike the periodic channels nothing drives the flow but the inlet, so the length of
+// the channel matters: entrance regions and separated flows such as that behind a
+// backward-facing step can develop and leave.
+use ndarray::prelude::*;
+
+use crate::mac::{self, Mac};
+use crate::macros::{assign, fill, i};
+use crate::norm::{norms, Norms};
+use crate::poisson::conjugate_gradient;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Inlet {
+    Uniform(f64),
+    // Poiseuille profile of the given bulk velocity
+    Parabolic(f64),
+    // u(y, H)
+    Function(fn(f64, f64) -> f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outlet {
+    // du/dx = 0, rescaled so that what leaves is what enters
+    ZeroGradient,
+    // u_t + U u_x = 0 with the bulk velocity U of the inlet, rescaled likewise
+    Convective,
+    // Fixed pressure on the outlet face; the projection sets the outflow
+    Pressure(f64),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub inlet: Inlet,
+    pub outlet: Outlet,
+    // See mac::Options
+    pub gamma: f64,
+    pub tolerance: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            inlet: Inlet::Parabolic(1.),
+            outlet: Outlet::ZeroGradient,
+            gamma: 0.,
+            tolerance: 1e-10,
+        };
+    }
+}
+
+impl Inlet {
+    // u at the centres y = (r - 1/2) dy of the inlet faces
+    pub fn profile(&self, ny: usize, dy: f64) -> Array1<f64> {
+        let h = ny as f64 * dy;
+        return Array::from_shape_fn(ny, |r| {
+            let y = (r as f64 + 0.5) * dy;
+            match *self {
+                Inlet::Uniform(u) => u,
+                Inlet::Parabolic(u) => 6. * u * y * (h - y) / h.powi(2),
+                Inlet::Function(f) => f(y, h),
+            }
+        });
+    }
+}
+
+// Marches until the largest change of u per unit time falls below `eps`; returns the
+// norms of the divergence after every step, or None when `max_steps` pass first or the
+// march blows up
+pub fn solve(
+    mac: &mut Mac,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+) -> Option<Vec<Norms>> {
+    let mut divergence = Vec::new();
+    for _ in 0..max_steps {
+        let un = mac.u.clone();
+        divergence.push(step(mac, dt, rho, nu, options));
+
+        let diff = (&mac.u - &un).fold(0., |a: f64, x| a.max(x.abs())) / dt;
+        // max skips NaN, so a blow-up shows in the sum
+        if !(diff + mac.u.sum()).is_finite() {
+            return None;
+        }
+        if diff <= eps {
+            return Some(divergence);
+        }
+    }
+    return None;
+}
+
+// One explicit step projected onto zero divergence; returns the norms of the divergence
+// of the cells afterwards
+pub fn step(mac: &mut Mac, dt: f64, rho: f64, nu: f64, options: &Options) -> Norms {
+    let (dx, dy) = (mac.dx, mac.dy);
+    let (ny, nx) = mac.cells();
+    let inlet = options.inlet.profile(ny, dy);
+    set_outflow(&mut mac.u, &inlet, dx, dt, options.outlet);
+    set_velocity_boundary_conditions(&mut mac.u, &mut mac.v, &inlet);
+
+    let (mut fu, mut fv) = mac::predictor(&mac.u, &mac.v, dx, dy, dt, nu, 0., options.gamma, false);
+    let pressure = match options.outlet {
+        Outlet::Pressure(p) => {
+            // The outlet face starts from zero gradient and is corrected like the interior
+            let un = i!(fu[1..-1, -2]).to_owned();
+            assign!(fu[1..-1, -1] = un);
+            Some(p)
+        }
+        _ => None,
+    };
+    set_velocity_boundary_conditions(&mut fu, &mut fv, &inlet);
+
+    // Projection: lap(p) = rho / dt div(u*), dp/dn = 0 but on a pressure outlet
+    let dirichlet = pressure.is_some();
+    let b = rho / dt * mac::divergence(&fu, &fv, dx, dy);
+    let a = |q: &Array2<f64>| -laplacian(q, dx, dy, dirichlet);
+    let q = conjugate_gradient(a, &-b, options.tolerance);
+    let level = match pressure {
+        Some(p) => p,
+        None => -q.mean().unwrap(),
+    };
+    mac.p = pad(&q, dirichlet) + level;
+    let p = &mac.p;
+
+    mac.u = fu;
+    mac.v = fv;
+    let last = if dirichlet { nx } else { nx - 1 };
+    for r in 1..=ny {
+        for c in 1..=last {
+            mac.u[[r, c]] -= dt / rho * (p[[r, c + 1]] - p[[r, c]]) / dx;
+        }
+    }
+    for r in 1..ny {
+        for c in 1..=nx {
+            mac.v[[r, c]] -= dt / rho * (p[[r + 1, c]] - p[[r, c]]) / dy;
+        }
+    }
+    set_velocity_boundary_conditions(&mut mac.u, &mut mac.v, &inlet);
+
+    return norms(&mac.divergence());
+}
+
+// Flow rate through each vertical face line x = c dx, c = 0..=nx
+pub fn flow_rates(mac: &Mac) -> Array1<f64> {
+    let u = &mac.u;
+    return i!(u[1..-1, ..]).sum_axis(Axis(0)) * mac.dy;
+}
+
+// Outlet faces from the outflow condition, rescaled to the inflow; a pressure outlet is
+// left to the projection
+fn set_outflow(u: &mut Array2<f64>, inlet: &Array1<f64>, dx: f64, dt: f64, outlet: Outlet) {
+    let mut out = match outlet {
+        Outlet::ZeroGradient => i!(u[1..-1, -2]).to_owned(),
+        Outlet::Convective => {
+            let bulk = inlet.mean().unwrap();
+            i!(u[1..-1, -1]) - bulk * dt / dx * (i!(u[1..-1, -1]) - i!(u[1..-1, -2]))
+        }
+        Outlet::Pressure(_) => return,
+    };
+    let (q_in, q_out) = (inlet.sum(), out.sum());
+    if q_out.abs() > f64::EPSILON {
+        out *= q_in / q_out;
+    } else {
+        out.fill(q_in / out.len() as f64);
+    }
+    assign!(u[1..-1, -1] = out);
+}
+
+// No-slip walls and the inlet through the ghost values; v leaves with zero gradient
+fn set_velocity_boundary_conditions(u: &mut Array2<f64>, v: &mut Array2<f64>, inlet: &Array1<f64>) {
+    assign!(u[1..-1, 0] = inlet);
+    let un = -i!(u[1, ..]).to_owned();
+    assign!(u[0, ..] = un);
+    let un = -i!(u[-2, ..]).to_owned();
+    assign!(u[-1, ..] = un);
+
+    fill!(v[0, ..] = 0.);
+    fill!(v[-1, ..] = 0.);
+    let vn = -i!(v[.., 1]).to_owned();
+    assign!(v[.., 0] = vn);
+    let vn = i!(v[.., -2]).to_owned();
+    assign!(v[.., -1] = vn);
+}
+
+// Five-point Laplacian of a cell field, dp/dn = 0 but for p = 0 on the outlet face when
+// `dirichlet`
+fn laplacian(q: &Array2<f64>, dx: f64, dy: f64, dirichlet: bool) -> Array2<f64> {
+    let q = pad(q, dirichlet);
+    return (i!(q[1..-1, 2..]) - 2. * i!(q[1..-1, 1..-1]) + i!(q[1..-1, ..-2])) / dx.powi(2)
+        + (i!(q[2.., 1..-1]) - 2. * i!(q[1..-1, 1..-1]) + i!(q[..-2, 1..-1])) / dy.powi(2);
+}
+
+// Mirrored ghosts, antisymmetric past the outlet when `dirichlet`
+fn pad(q: &Array2<f64>, dirichlet: bool) -> Array2<f64> {
+    let mut p = mac::pad(q, false);
+    if dirichlet {
+        let pn = -i!(p[.., -2]).to_owned();
+        assign!(p[.., -1] = pn);
+    }
+    return p;
+}
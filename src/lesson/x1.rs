@@ -1,7 +1,11 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/01_Step_1.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
+use crate::advection::{self, Scheme};
 use crate::macros::fill;
+use crate::monitor::Monitor;
+use crate::rk::{self, Integrator};
 
 pub fn default() -> (Array1<f64>, usize, f64, f64, f64) {
     let nx = 41;
@@ -21,12 +25,47 @@ pub fn default() -> (Array1<f64>, usize, f64, f64, f64) {
 }
 
 pub fn solve(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, c: f64) {
-    let nx = u.shape()[0];
-    for _ in 0..nt {
-        let un = u.clone();
+    solve_with(u, nt, dx, dt, c, Scheme::Upwind);
+}
 
-        for ith in 1..nx {
-            u[ith] = un[ith] - c * dt / dx * (un[ith] - un[ith - 1]);
-        }
+pub fn solve_monitored<W: Write>(
+    u: &mut Array1<f64>,
+    nt: usize,
+    dx: f64,
+    dt: f64,
+    c: f64,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    monitor.march(
+        u,
+        nt,
+        dt,
+        dx,
+        1.,
+        false,
+        |u| {
+            solve(u, 1, dx, dt, c);
+            return true;
+        },
+        |u| vec![("u", u.view().insert_axis(Axis(0)))],
+    )?;
+    return Ok(());
+}
+
+pub fn solve_with(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, c: f64, scheme: Scheme) {
+    advection::linear(u, nt, dx, dt, c, scheme);
+}
+
+// Backward-difference right-hand side of u_t = L(u), zero at the inflow node
+pub fn rhs(u: &Array1<f64>, dx: f64, c: f64) -> Array1<f64> {
+    let nx = u.shape()[0];
+    let mut l = Array::zeros(nx);
+    for ith in 1..nx {
+        l[ith] = -c / dx * (u[ith] - u[ith - 1]);
     }
+    return l;
+}
+
+pub fn solve_rk(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, c: f64, integrator: Integrator) {
+    rk::march(u, nt, dt, integrator, |_, u| rhs(u, dx, c));
 }
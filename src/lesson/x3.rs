@@ -1,7 +1,12 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/03_CFL_Condition.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
+use crate::advection::{self, Scheme};
+use crate::lesson::x1;
 use crate::macros::fill;
+use crate::monitor::Monitor;
+use crate::rk::{self, Integrator};
 
 pub fn default() -> (Array1<f64>, usize, f64, f64, f64) {
     let nx = 85;
@@ -22,12 +27,38 @@ pub fn default() -> (Array1<f64>, usize, f64, f64, f64) {
 }
 
 pub fn solve(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, c: f64) {
-    let nx = u.shape()[0];
-    for _ in 0..nt {
-        let un = u.clone();
-
-        for ith in 1..nx {
-            u[ith] = un[ith] - c * dt / dx * (un[ith] - un[ith - 1]);
-        }
-    }
+    solve_with(u, nt, dx, dt, c, Scheme::Upwind);
+}
+
+pub fn solve_monitored<W: Write>(
+    u: &mut Array1<f64>,
+    nt: usize,
+    dx: f64,
+    dt: f64,
+    c: f64,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    monitor.march(
+        u,
+        nt,
+        dt,
+        dx,
+        1.,
+        false,
+        |u| {
+            solve(u, 1, dx, dt, c);
+            return true;
+        },
+        |u| vec![("u", u.view().insert_axis(Axis(0)))],
+    )?;
+    return Ok(());
+}
+
+pub fn solve_with(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, c: f64, scheme: Scheme) {
+    advection::linear(u, nt, dx, dt, c, scheme);
+}
+
+// The same linear convection as x1
+pub fn solve_rk(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, c: f64, integrator: Integrator) {
+    rk::march(u, nt, dt, integrator, |_, u| x1::rhs(u, dx, c));
 }
@@ -1,7 +1,11 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/04_Step_3.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
+use crate::implicit::{self, Method};
 use crate::macros::fill;
+use crate::monitor::Monitor;
+use crate::rk::{self, Integrator};
 
 pub fn default() -> (Array1<f64>, usize, f64, f64, f64) {
     let nx = 41;
@@ -31,3 +35,45 @@ pub fn solve(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, nu: f64) {
         }
     }
 }
+
+pub fn solve_monitored<W: Write>(
+    u: &mut Array1<f64>,
+    nt: usize,
+    dx: f64,
+    dt: f64,
+    nu: f64,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    monitor.march(
+        u,
+        nt,
+        dt,
+        dx,
+        1.,
+        false,
+        |u| {
+            solve(u, 1, dx, dt, nu);
+            return true;
+        },
+        |u| vec![("u", u.view().insert_axis(Axis(0)))],
+    )?;
+    return Ok(());
+}
+
+// Right-hand side of u_t = L(u), zero at the end nodes
+pub fn rhs(u: &Array1<f64>, dx: f64, nu: f64) -> Array1<f64> {
+    let nx = u.shape()[0];
+    let mut l = Array::zeros(nx);
+    for ith in 1..nx - 1 {
+        l[ith] = nu / dx.powi(2) * (u[ith + 1] - 2. * u[ith] + u[ith - 1]);
+    }
+    return l;
+}
+
+pub fn solve_rk(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, nu: f64, integrator: Integrator) {
+    rk::march(u, nt, dt, integrator, |_, u| rhs(u, dx, nu));
+}
+
+pub fn solve_implicit(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, nu: f64, method: Method) {
+    implicit::diffusion(u, nt, dx, dt, nu, method);
+}
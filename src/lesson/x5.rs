@@ -1,6 +1,12 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/05_Step_4.ipynb
 use ndarray::prelude::*;
 use std::f64::consts::PI;
+use std::io::{self, Write};
+
+use crate::analytic::burgers;
+use crate::monitor::Monitor;
+use crate::rk::{self, Integrator};
+use crate::weno::{self, Splitting, Weno};
 
 pub fn default() -> (Array1<f64>, usize, f64, f64, f64) {
     let nx = 101;
@@ -13,7 +19,7 @@ pub fn default() -> (Array1<f64>, usize, f64, f64, f64) {
 
     let mut u = Array::ones(nx);
     for (ith, x0) in Array::linspace(0., 2. * PI, nx).into_iter().enumerate() {
-        u[ith] = ufunc(0., x0, nu);
+        u[ith] = burgers(x0, 0., nu);
     }
 
     return (u, nt, dx, dt, nu);
@@ -35,15 +41,60 @@ pub fn solve(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, nu: f64) {
     }
 }
 
-fn ufunc(t: f64, x: f64, nu: f64) -> f64 {
-    return -2.
-        * nu
-        * (-(-8. * t + 2. * x) * (-(-4. * t + x).powi(2) / (4. * nu * (t + 1.))).exp()
-            / (4. * nu * (t + 1.))
-            - (-8. * t + 2. * x - 4. * PI)
-                * (-(-4. * t + x - 2. * PI).powi(2) / (4. * nu * (t + 1.))).exp()
-                / (4. * nu * (t + 1.)))
-        / ((-(-4. * t + x - 2. * PI).powi(2) / (4. * nu * (t + 1.))).exp()
-            + (-(-4. * t + x).powi(2) / (4. * nu * (t + 1.))).exp())
-        + 4.;
+pub fn solve_monitored<W: Write>(
+    u: &mut Array1<f64>,
+    nt: usize,
+    dx: f64,
+    dt: f64,
+    nu: f64,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    monitor.march(
+        u,
+        nt,
+        dt,
+        dx,
+        1.,
+        false,
+        |u| {
+            solve(u, 1, dx, dt, nu);
+            return true;
+        },
+        |u| vec![("u", u.view().insert_axis(Axis(0)))],
+    )?;
+    return Ok(());
+}
+
+pub fn solve_weno(
+    u: &mut Array1<f64>,
+    nt: usize,
+    dx: f64,
+    dt: f64,
+    nu: f64,
+    weno: Weno,
+    splitting: Splitting,
+) {
+    // The last node repeats the first one
+    let nx = u.shape()[0];
+    let mut un = u.slice(s![..nx - 1]).to_owned();
+    weno::burgers(&mut un, nt, dx, dt, nu, weno, splitting, true);
+    u.slice_mut(s![..nx - 1]).assign(&un);
+    u[nx - 1] = u[0];
+}
+
+// Right-hand side of u_t = L(u); the last node repeats the first one
+pub fn rhs(u: &Array1<f64>, dx: f64, nu: f64) -> Array1<f64> {
+    let nx = u.shape()[0];
+    let mut l = Array::zeros(nx);
+    for ith in 0..nx - 1 {
+        let west = if ith == 0 { u[nx - 2] } else { u[ith - 1] };
+        l[ith] =
+            -u[ith] / dx * (u[ith] - west) + nu / dx.powi(2) * (u[ith + 1] - 2. * u[ith] + west);
+    }
+    l[nx - 1] = l[0];
+    return l;
+}
+
+pub fn solve_rk(u: &mut Array1<f64>, nt: usize, dx: f64, dt: f64, nu: f64, integrator: Integrator) {
+    rk::march(u, nt, dt, integrator, |_, u| rhs(u, dx, nu));
 }
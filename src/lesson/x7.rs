@@ -1,7 +1,11 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/07_Step_5.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
 use crate::macros::{assign, fill, i};
+use crate::monitor::Monitor;
+use crate::rk::{self, Integrator};
+use crate::tvd::{self, Limiter};
 
 pub fn default() -> (Array2<f64>, usize, f64, f64, f64, f64) {
     let nx = 81;
@@ -27,17 +31,81 @@ pub fn default() -> (Array2<f64>, usize, f64, f64, f64, f64) {
 
 pub fn solve(u: &mut Array2<f64>, nt: usize, dx: f64, dy: f64, dt: f64, c: f64) {
     for _ in 0..nt + 1 {
-        let un = u.clone();
-
-        assign!(
-            u[1.., 1..] = i!(un[1.., 1..])
-                - (c * dt / dx * (i!(un[1.., 1..]) - i!(un[1.., ..-1])))
-                - (c * dt / dy * (i!(un[1.., 1..]) - i!(un[..-1, 1..])))
-        );
-
-        fill!(u[0, ..] = 1.);
-        fill!(u[-1, ..] = 1.);
-        fill!(u[.., 0] = 1.);
-        fill!(u[.., -1] = 1.);
+        step(u, dx, dy, dt, c);
     }
 }
+
+// Like `solve`, it runs nt + 1 steps
+pub fn solve_monitored<W: Write>(
+    u: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    c: f64,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    monitor.march(
+        u,
+        nt + 1,
+        dt,
+        dx,
+        dy,
+        false,
+        |u| {
+            step(u, dx, dy, dt, c);
+            return true;
+        },
+        |u| vec![("u", u.view())],
+    )?;
+    return Ok(());
+}
+
+fn step(u: &mut Array2<f64>, dx: f64, dy: f64, dt: f64, c: f64) {
+    let un = u.clone();
+
+    assign!(
+        u[1.., 1..] = i!(un[1.., 1..])
+            - (c * dt / dx * (i!(un[1.., 1..]) - i!(un[1.., ..-1])))
+            - (c * dt / dy * (i!(un[1.., 1..]) - i!(un[..-1, 1..])))
+    );
+
+    fill!(u[0, ..] = 1.);
+    fill!(u[-1, ..] = 1.);
+    fill!(u[.., 0] = 1.);
+    fill!(u[.., -1] = 1.);
+}
+
+pub fn solve_tvd(
+    u: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    c: f64,
+    limiter: Limiter,
+) {
+    tvd::linear2(u, nt + 1, dx, dy, dt, c, limiter);
+}
+
+// Backward-difference right-hand side of u_t = L(u), zero on the edges
+pub fn rhs(u: &Array2<f64>, dx: f64, dy: f64, c: f64) -> Array2<f64> {
+    let mut l = Array::zeros(u.raw_dim());
+    assign!(
+        l[1..-1, 1..-1] = -c / dx * (i!(u[1..-1, 1..-1]) - i!(u[1..-1, ..-2]))
+            - c / dy * (i!(u[1..-1, 1..-1]) - i!(u[..-2, 1..-1]))
+    );
+    return l;
+}
+
+pub fn solve_rk(
+    u: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    c: f64,
+    integrator: Integrator,
+) {
+    rk::march(u, nt + 1, dt, integrator, |_, u| rhs(u, dx, dy, c));
+}
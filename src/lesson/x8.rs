@@ -1,7 +1,11 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/08_Step_6.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
 use crate::macros::{assign, fill, i};
+use crate::monitor::Monitor;
+use crate::rk::{self, Integrator};
+use crate::upwind::{convection, Upwinding};
 
 pub fn default() -> (Array2<f64>, Array2<f64>, usize, f64, f64, f64) {
     let nx = 101;
@@ -27,28 +31,102 @@ pub fn default() -> (Array2<f64>, Array2<f64>, usize, f64, f64, f64) {
 }
 
 pub fn solve(u: &mut Array2<f64>, v: &mut Array2<f64>, nt: usize, dx: f64, dy: f64, dt: f64) {
-    for _ in 0..nt + 1 {
-        let un = u.clone();
-        let vn = v.clone();
+    solve_with(u, v, nt, dx, dy, dt, Upwinding::Backward);
+}
 
-        assign!(
-            u[1.., 1..] = i!(un[1.., 1..])
-                - (i!(un[1.., 1..]) * dt / dx * (i!(un[1.., 1..]) - i!(un[1.., ..-1])))
-                - (i!(vn[1.., 1..]) * dt / dy * (i!(un[1.., 1..]) - i!(un[..-1, 1..])))
-        );
-        assign!(
-            v[1.., 1..] = i!(vn[1.., 1..])
-                - (i!(un[1.., 1..]) * dt / dx * (i!(vn[1.., 1..]) - i!(vn[1.., ..-1])))
-                - (i!(vn[1.., 1..]) * dt / dy * (i!(vn[1.., 1..]) - i!(vn[..-1, 1..])))
-        );
+// Like `solve`, it runs nt + 1 steps
+pub fn solve_monitored<W: Write>(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    monitor.march(
+        &mut (u, v),
+        nt + 1,
+        dt,
+        dx,
+        dy,
+        false,
+        |(u, v)| {
+            step(u, v, dx, dy, dt, Upwinding::Backward);
+            return true;
+        },
+        |(u, v)| vec![("u", u.view()), ("v", v.view())],
+    )?;
+    return Ok(());
+}
 
-        fill!(u[0, ..] = 1.);
-        fill!(u[-1, ..] = 1.);
-        fill!(u[.., 0] = 1.);
-        fill!(u[.., -1] = 1.);
-        fill!(v[0, ..] = 1.);
-        fill!(v[-1, ..] = 1.);
-        fill!(v[.., 0] = 1.);
-        fill!(v[.., -1] = 1.);
+pub fn solve_with(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    upwinding: Upwinding,
+) {
+    for _ in 0..nt + 1 {
+        step(u, v, dx, dy, dt, upwinding);
     }
 }
+
+fn step(u: &mut Array2<f64>, v: &mut Array2<f64>, dx: f64, dy: f64, dt: f64, upwinding: Upwinding) {
+    let un = u.clone();
+    let vn = v.clone();
+    let cu = convection(&un, &un, &vn, dx, dy, upwinding, false);
+    let cv = convection(&vn, &un, &vn, dx, dy, upwinding, false);
+
+    assign!(u[1..-1, 1..-1] = i!(un[1..-1, 1..-1]) - dt * i!(cu[1..-1, 1..-1]));
+    assign!(v[1..-1, 1..-1] = i!(vn[1..-1, 1..-1]) - dt * i!(cv[1..-1, 1..-1]));
+
+    fill!(u[0, ..] = 1.);
+    fill!(u[-1, ..] = 1.);
+    fill!(u[.., 0] = 1.);
+    fill!(u[.., -1] = 1.);
+    fill!(v[0, ..] = 1.);
+    fill!(v[-1, ..] = 1.);
+    fill!(v[.., 0] = 1.);
+    fill!(v[.., -1] = 1.);
+}
+
+// Right-hand sides of u_t = L(u, v) and v_t = M(u, v), zero on the edges
+pub fn rhs(
+    u: &Array2<f64>,
+    v: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    upwinding: Upwinding,
+) -> (Array2<f64>, Array2<f64>) {
+    return (
+        -convection(u, u, v, dx, dy, upwinding, false),
+        -convection(v, u, v, dx, dy, upwinding, false),
+    );
+}
+
+pub fn solve_rk(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    upwinding: Upwinding,
+    integrator: Integrator,
+) {
+    // u and v are advanced together as w[0] and w[1]
+    let mut w = ndarray::stack(Axis(0), &[u.view(), v.view()]).unwrap();
+    rk::march(&mut w, nt + 1, dt, integrator, |_, w| {
+        let (u, v) = (
+            w.index_axis(Axis(0), 0).to_owned(),
+            w.index_axis(Axis(0), 1).to_owned(),
+        );
+        let (l, m) = rhs(&u, &v, dx, dy, upwinding);
+        ndarray::stack(Axis(0), &[l.view(), m.view()]).unwrap()
+    });
+    u.assign(&w.index_axis(Axis(0), 0));
+    v.assign(&w.index_axis(Axis(0), 1));
+}
@@ -1,7 +1,11 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/09_Step_7.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
-use crate::macros::{assign, fill, i};
+use crate::implicit::{self, Method};
+use crate::macros::{add, assign, fill, i};
+use crate::monitor::Monitor;
+use crate::rk::{self, Integrator};
 
 pub fn default() -> (Array2<f64>, usize, f64, f64, f64, f64) {
     let nx = 31;
@@ -27,19 +31,105 @@ pub fn default() -> (Array2<f64>, usize, f64, f64, f64, f64) {
 
 pub fn solve(u: &mut Array2<f64>, nt: usize, dx: f64, dy: f64, dt: f64, nu: f64) {
     for _ in 0..nt + 1 {
-        let un = u.clone();
-
-        assign!(
-            u[1..-1, 1..-1] = i!(un[1..-1, 1..-1])
-                + nu * dt / dx.powi(2)
-                    * (i!(un[1..-1, 2..]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[1..-1, ..-2]))
-                + nu * dt / dy.powi(2)
-                    * (i!(un[2.., 1..-1]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[..-2, 1..-1]))
-        );
-
-        fill!(u[0, ..] = 1.);
-        fill!(u[-1, ..] = 1.);
-        fill!(u[.., 0] = 1.);
-        fill!(u[.., -1] = 1.);
+        step(u, dx, dy, dt, nu, None);
     }
 }
+
+// Like `solve`, it runs nt + 1 steps
+pub fn solve_monitored<W: Write>(
+    u: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    monitor.march(
+        u,
+        nt + 1,
+        dt,
+        dx,
+        dy,
+        false,
+        |u| {
+            step(u, dx, dy, dt, nu, None);
+            return true;
+        },
+        |u| vec![("u", u.view())],
+    )?;
+    return Ok(());
+}
+
+// `source(t)` is added to the right-hand side of every step
+pub fn solve_forced<F>(
+    u: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    source: F,
+) where
+    F: Fn(f64) -> Array2<f64>,
+{
+    for n in 0..nt + 1 {
+        let s = source(n as f64 * dt);
+        step(u, dx, dy, dt, nu, Some(&s));
+    }
+}
+
+fn step(u: &mut Array2<f64>, dx: f64, dy: f64, dt: f64, nu: f64, source: Option<&Array2<f64>>) {
+    let un = u.clone();
+
+    assign!(
+        u[1..-1, 1..-1] = i!(un[1..-1, 1..-1])
+            + nu * dt / dx.powi(2)
+                * (i!(un[1..-1, 2..]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[1..-1, ..-2]))
+            + nu * dt / dy.powi(2)
+                * (i!(un[2.., 1..-1]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[..-2, 1..-1]))
+    );
+    if let Some(s) = source {
+        add!(u[1..-1, 1..-1] += dt * i!(s[1..-1, 1..-1]));
+    }
+
+    fill!(u[0, ..] = 1.);
+    fill!(u[-1, ..] = 1.);
+    fill!(u[.., 0] = 1.);
+    fill!(u[.., -1] = 1.);
+}
+
+// Right-hand side of u_t = L(u), zero on the edges
+pub fn rhs(u: &Array2<f64>, dx: f64, dy: f64, nu: f64) -> Array2<f64> {
+    let mut l = Array::zeros(u.raw_dim());
+    assign!(
+        l[1..-1, 1..-1] = nu / dx.powi(2)
+            * (i!(u[1..-1, 2..]) - 2. * i!(u[1..-1, 1..-1]) + i!(u[1..-1, ..-2]))
+            + nu / dy.powi(2) * (i!(u[2.., 1..-1]) - 2. * i!(u[1..-1, 1..-1]) + i!(u[..-2, 1..-1]))
+    );
+    return l;
+}
+
+pub fn solve_rk(
+    u: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    integrator: Integrator,
+) {
+    rk::march(u, nt + 1, dt, integrator, |_, u| rhs(u, dx, dy, nu));
+}
+
+pub fn solve_implicit(
+    u: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    method: Method,
+) {
+    implicit::diffusion2(u, nt + 1, dx, dy, dt, nu, method);
+}
@@ -1,7 +1,11 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/10_Step_8.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
-use crate::macros::{assign, fill, i};
+use crate::macros::{add, assign, fill, i};
+use crate::monitor::Monitor;
+use crate::rk::{self, Integrator};
+use crate::upwind::{convection, Upwinding};
 
 pub fn default() -> (Array2<f64>, Array2<f64>, usize, f64, f64, f64, f64) {
     let nx = 41;
@@ -35,37 +39,163 @@ pub fn solve(
     dy: f64,
     dt: f64,
     nu: f64,
+) {
+    solve_with(u, v, nt, dx, dy, dt, nu, Upwinding::Backward);
+}
+
+// Like `solve`, it runs nt + 1 steps
+pub fn solve_monitored<W: Write>(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    monitor.march(
+        &mut (u, v),
+        nt + 1,
+        dt,
+        dx,
+        dy,
+        false,
+        |(u, v)| {
+            step(u, v, dx, dy, dt, nu, Upwinding::Backward, None);
+            return true;
+        },
+        |(u, v)| vec![("u", u.view()), ("v", v.view())],
+    )?;
+    return Ok(());
+}
+
+pub fn solve_with(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    upwinding: Upwinding,
 ) {
     for _ in 0..nt + 1 {
-        let un = u.clone();
-        let vn = v.clone();
+        step(u, v, dx, dy, dt, nu, upwinding, None);
+    }
+}
 
-        assign!(
-            u[1..-1, 1..-1] = i!(un[1..-1, 1..-1])
-                - dt / dx * i!(un[1..-1, 1..-1]) * (i!(un[1..-1, 1..-1]) - i!(un[1..-1, ..-2]))
-                - dt / dy * i!(vn[1..-1, 1..-1]) * (i!(un[1..-1, 1..-1]) - i!(un[..-2, 1..-1]))
-                + nu * dt / dx.powi(2)
-                    * (i!(un[1..-1, 2..]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[1..-1, ..-2]))
-                + nu * dt / dy.powi(2)
-                    * (i!(un[2.., 1..-1]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[..-2, 1..-1]))
-        );
-        assign!(
-            v[1..-1, 1..-1] = i!(vn[1..-1, 1..-1])
-                - dt / dx * i!(un[1..-1, 1..-1]) * (i!(vn[1..-1, 1..-1]) - i!(vn[1..-1, ..-2]))
-                - dt / dy * i!(vn[1..-1, 1..-1]) * (i!(vn[1..-1, 1..-1]) - i!(vn[..-2, 1..-1]))
-                + nu * dt / dx.powi(2)
-                    * (i!(vn[1..-1, 2..]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[1..-1, ..-2]))
-                + nu * dt / dy.powi(2)
-                    * (i!(vn[2.., 1..-1]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[..-2, 1..-1]))
-        );
+// `source(t)` is added to the right-hand sides of the u and v equations of every step
+pub fn solve_forced<F>(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    upwinding: Upwinding,
+    source: F,
+) where
+    F: Fn(f64) -> (Array2<f64>, Array2<f64>),
+{
+    for n in 0..nt + 1 {
+        let s = source(n as f64 * dt);
+        step(u, v, dx, dy, dt, nu, upwinding, Some(&s));
+    }
+}
+
+fn step(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    upwinding: Upwinding,
+    source: Option<&(Array2<f64>, Array2<f64>)>,
+) {
+    let un = u.clone();
+    let vn = v.clone();
+    let cu = convection(&un, &un, &vn, dx, dy, upwinding, false);
+    let cv = convection(&vn, &un, &vn, dx, dy, upwinding, false);
 
-        fill!(u[0, ..] = 1.);
-        fill!(u[-1, ..] = 1.);
-        fill!(u[.., 0] = 1.);
-        fill!(u[.., -1] = 1.);
-        fill!(v[0, ..] = 1.);
-        fill!(v[-1, ..] = 1.);
-        fill!(v[.., 0] = 1.);
-        fill!(v[.., -1] = 1.);
+    assign!(
+        u[1..-1, 1..-1] = i!(un[1..-1, 1..-1])
+            - dt * i!(cu[1..-1, 1..-1])
+            + nu * dt / dx.powi(2)
+                * (i!(un[1..-1, 2..]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[1..-1, ..-2]))
+            + nu * dt / dy.powi(2)
+                * (i!(un[2.., 1..-1]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[..-2, 1..-1]))
+    );
+    assign!(
+        v[1..-1, 1..-1] = i!(vn[1..-1, 1..-1])
+            - dt * i!(cv[1..-1, 1..-1])
+            + nu * dt / dx.powi(2)
+                * (i!(vn[1..-1, 2..]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[1..-1, ..-2]))
+            + nu * dt / dy.powi(2)
+                * (i!(vn[2.., 1..-1]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[..-2, 1..-1]))
+    );
+    if let Some((su, sv)) = source {
+        add!(u[1..-1, 1..-1] += dt * i!(su[1..-1, 1..-1]));
+        add!(v[1..-1, 1..-1] += dt * i!(sv[1..-1, 1..-1]));
     }
+
+    fill!(u[0, ..] = 1.);
+    fill!(u[-1, ..] = 1.);
+    fill!(u[.., 0] = 1.);
+    fill!(u[.., -1] = 1.);
+    fill!(v[0, ..] = 1.);
+    fill!(v[-1, ..] = 1.);
+    fill!(v[.., 0] = 1.);
+    fill!(v[.., -1] = 1.);
+}
+
+// Right-hand sides of u_t = L(u, v) and v_t = M(u, v), zero on the edges
+pub fn rhs(
+    u: &Array2<f64>,
+    v: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    nu: f64,
+    upwinding: Upwinding,
+) -> (Array2<f64>, Array2<f64>) {
+    let mut l = -convection(u, u, v, dx, dy, upwinding, false);
+    let mut m = -convection(v, u, v, dx, dy, upwinding, false);
+    add!(
+        l[1..-1, 1..-1] += nu / dx.powi(2)
+            * (i!(u[1..-1, 2..]) - 2. * i!(u[1..-1, 1..-1]) + i!(u[1..-1, ..-2]))
+            + nu / dy.powi(2) * (i!(u[2.., 1..-1]) - 2. * i!(u[1..-1, 1..-1]) + i!(u[..-2, 1..-1]))
+    );
+    add!(
+        m[1..-1, 1..-1] += nu / dx.powi(2)
+            * (i!(v[1..-1, 2..]) - 2. * i!(v[1..-1, 1..-1]) + i!(v[1..-1, ..-2]))
+            + nu / dy.powi(2) * (i!(v[2.., 1..-1]) - 2. * i!(v[1..-1, 1..-1]) + i!(v[..-2, 1..-1]))
+    );
+    return (l, m);
+}
+
+pub fn solve_rk(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    upwinding: Upwinding,
+    integrator: Integrator,
+) {
+    // u and v are advanced together as w[0] and w[1]
+    let mut w = ndarray::stack(Axis(0), &[u.view(), v.view()]).unwrap();
+    rk::march(&mut w, nt + 1, dt, integrator, |_, w| {
+        let (u, v) = (
+            w.index_axis(Axis(0), 0).to_owned(),
+            w.index_axis(Axis(0), 1).to_owned(),
+        );
+        let (l, m) = rhs(&u, &v, dx, dy, nu, upwinding);
+        ndarray::stack(Axis(0), &[l.view(), m.view()]).unwrap()
+    });
+    u.assign(&w.index_axis(Axis(0), 0));
+    v.assign(&w.index_axis(Axis(0), 1));
 }
@@ -1,7 +1,47 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/14_Step_11.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
-use crate::macros::{assign, fill, i};
+use crate::implicit::{helmholtz, Viscous};
+use crate::macros::{add, assign, fill, i};
+use crate::monitor::Monitor;
+use crate::norm::Norms;
+use crate::obstacle::{self, Forcing};
+use crate::poisson::{self, Boundary, PressureSolver};
+use crate::projection::{self, Case};
+use crate::scalar;
+use crate::upwind::{convection, Upwinding};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PressureBc {
+    // dp/dn = 0 on the walls and p = 0 along the lid, as in CFDPython
+    Lid,
+    // dp/dn = 0 everywhere, p = 0 at the node (j, i), which must lie on the grid
+    Reference(usize, usize),
+    // dp/dn = 0 everywhere, mean(p) = 0
+    ZeroMean,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub pressure: PressureSolver,
+    pub convection: Upwinding,
+    pub viscous: Viscous,
+    pub pressure_bc: PressureBc,
+    pub lid: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            pressure: PressureSolver::Jacobi(50),
+            convection: Upwinding::Backward,
+            viscous: Viscous::Explicit,
+            pressure_bc: PressureBc::Lid,
+            lid: 1.,
+        };
+    }
+}
 
 pub fn default() -> (
     Array2<f64>,
@@ -45,43 +85,315 @@ pub fn solve(
     dt: f64,
     rho: f64,
     nu: f64,
+) {
+    let options = Options {
+        pressure: PressureSolver::Jacobi(nit),
+        ..Default::default()
+    };
+    solve_with(u, v, p, nt, dx, dy, dt, rho, nu, &options);
+}
+
+pub fn solve_with(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    options: &Options,
+) {
+    let mut b = Array::zeros(u.raw_dim());
+    for _ in 0..nt {
+        step(u, v, p, &mut b, dx, dy, dt, rho, nu, options, None);
+    }
+}
+
+pub fn solve_monitored<W: Write>(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    options: &Options,
+    monitor: &mut Monitor<W>,
+) -> io::Result<()> {
+    let mut b = Array::zeros(u.raw_dim());
+    monitor.march(
+        &mut (u, v, p),
+        nt,
+        dt,
+        dx,
+        dy,
+        false,
+        |(u, v, p)| {
+            step(u, v, p, &mut b, dx, dy, dt, rho, nu, options, None);
+            return true;
+        },
+        |(u, v, p)| vec![("u", u.view()), ("v", v.view()), ("p", p.view())],
+    )?;
+    return Ok(());
+}
+
+// Fractional steps instead of the lesson's pressure Poisson update; returns the norms of
+// the divergence after every step
+pub fn solve_projection(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    options: &projection::Options,
+) -> Vec<Norms> {
+    return (0..nt)
+        .map(|_| projection::step(u, v, p, dx, dy, dt, rho, nu, Case::Cavity(1.), options))
+        .collect();
+}
+
+// `solve_with` carrying the passive scalar phi, which each step advances with the velocity
+// it starts from
+pub fn solve_scalar(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    phi: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    kappa: f64,
+    options: &Options,
+    scalar: &scalar::Options,
+    source: Option<&Array2<f64>>,
+) {
+    let mut b = Array::zeros(u.raw_dim());
+    for _ in 0..nt {
+        scalar::step(phi, u, v, dx, dy, dt, kappa, false, scalar, source);
+        step(u, v, p, &mut b, dx, dy, dt, rho, nu, options, None);
+    }
+}
+
+// `solve_with` around the solid body of fractions `alpha` (see obstacle)
+pub fn solve_masked(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    options: &Options,
+    alpha: &Array2<f64>,
+    forcing: Forcing,
 ) {
     let mut b = Array::zeros(u.raw_dim());
     for _ in 0..nt {
+        step(u, v, p, &mut b, dx, dy, dt, rho, nu, options, None);
+        obstacle::force(u, v, alpha, forcing);
+    }
+}
+
+// `solve_with` for natural convection: the temperature t, advanced as a passive scalar,
+// drives the flow through the Boussinesq body force g beta (t - t_ref) along y
+pub fn solve_boussinesq(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    t: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    kappa: f64,
+    g_beta: f64,
+    t_ref: f64,
+    options: &Options,
+    scalar: &scalar::Options,
+) {
+    let mut b = Array::zeros(u.raw_dim());
+    let mut source = (Array::zeros(u.raw_dim()), Array::zeros(u.raw_dim()));
+    for _ in 0..nt {
+        source.1 = t.mapv(|t| g_beta * (t - t_ref));
+        scalar::step(t, u, v, dx, dy, dt, kappa, false, scalar, None);
+        step(u, v, p, &mut b, dx, dy, dt, rho, nu, options, Some(&source));
+    }
+}
+
+// `source(t)` is added to the right-hand sides of the momentum equations of every step
+pub fn solve_forced<F>(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    options: &Options,
+    source: F,
+) where
+    F: Fn(f64) -> (Array2<f64>, Array2<f64>),
+{
+    let mut b = Array::zeros(u.raw_dim());
+    for n in 0..nt {
+        let s = source(n as f64 * dt);
+        step(u, v, p, &mut b, dx, dy, dt, rho, nu, options, Some(&s));
+    }
+}
+
+// Marches until the largest velocity change per unit time falls below `eps`; returns the
+// number of steps, or None when `max_steps` pass first or the march blows up
+pub fn steady(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+) -> Option<usize> {
+    let mut b = Array::zeros(u.raw_dim());
+    for stepcount in 1..=max_steps {
         let un = u.clone();
         let vn = v.clone();
-        set_b(&mut b, &u, &v, dx, dy, dt, rho);
-        set_pressure_poisson(p, &b, dx, dy, nit);
+        step(u, v, p, &mut b, dx, dy, dt, rho, nu, options, None);
 
-        assign!(
-            u[1..-1, 1..-1] =  i!(un[1..-1, 1..-1])
-                - i!(un[1..-1, 1..-1]) * dt / dx * (i!(un[1..-1, 1..-1]) - i!(un[1..-1, ..-2]))
-                - i!(vn[1..-1, 1..-1]) * dt / dy * (i!(un[1..-1, 1..-1]) - i!(un[..-2, 1..-1]))
-                - dt / (2. * rho * dx) * (i!(p[1..-1, 2..]) - i!(p[1..-1, ..-2]))
-                + nu * (dt / dx.powi(2)
-                    * (i!(un[1..-1, 2..]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[1..-1, ..-2]))
-                    + dt / dy.powi(2)
-                        * (i!(un[2.., 1..-1]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[..-2, 1..-1])))
+        let diff = (&*u - &un)
+            .iter()
+            .chain((&*v - &vn).iter())
+            .fold(0., |a: f64, x| a.max(x.abs()))
+            / dt;
+        // max skips NaN, so a blow-up shows in the sums
+        if !(diff + u.sum() + v.sum()).is_finite() {
+            return None;
+        }
+        if diff <= eps {
+            return Some(stepcount);
+        }
+    }
+    return None;
+}
+
+fn step(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    b: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    options: &Options,
+    source: Option<&(Array2<f64>, Array2<f64>)>,
+) {
+    if let PressureBc::Reference(j, i) = options.pressure_bc {
+        let (ny, nx) = p.dim();
+        assert!(
+            j < ny && i < nx,
+            "pressure reference ({}, {}) is outside the {} x {} grid",
+            j,
+            i,
+            ny,
+            nx
         );
-        assign!(
-            v[1..-1, 1..-1] = i!(vn[1..-1, 1..-1])
-                - i!(un[1..-1, 1..-1]) * dt / dx * (i!(vn[1..-1, 1..-1]) - i!(vn[1..-1, ..-2]))
-                - i!(vn[1..-1, 1..-1]) * dt / dy * (i!(vn[1..-1, 1..-1]) - i!(vn[..-2, 1..-1]))
-                - dt / (2. * rho * dy) * (i!(p[2.., 1..-1]) - i!(p[..-2, 1..-1]))
-                + nu * (dt / dx.powi(2)
-                    * (i!(vn[1..-1, 2..]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[1..-1, ..-2]))
-                    + dt / dy.powi(2)
-                        * (i!(vn[2.., 1..-1]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[..-2, 1..-1])))
+    }
+    let un = u.clone();
+    let vn = v.clone();
+    // Weight of the viscous terms at the old time level
+    let explicit = match options.viscous {
+        Viscous::Explicit => 1.,
+        Viscous::Implicit(method) => 1. - method.theta(),
+    };
+    let cu = convection(&un, &un, &vn, dx, dy, options.convection, false);
+    let cv = convection(&vn, &un, &vn, dx, dy, options.convection, false);
+    set_b(b, u, v, dx, dy, dt, rho);
+    if let Some((su, sv)) = source {
+        // The divergence of the momentum source enters the pressure equation
+        add!(
+            b[1..-1, 1..-1] += rho
+                * ((i!(su[1..-1, 2..]) - i!(su[1..-1, ..-2])) / (2. * dx)
+                    + (i!(sv[2.., 1..-1]) - i!(sv[..-2, 1..-1])) / (2. * dy))
         );
+    }
+    match options.pressure {
+        PressureSolver::Jacobi(nit) => set_pressure_poisson(p, b, dx, dy, nit, options.pressure_bc),
+        PressureSolver::Spectral => match options.pressure_bc {
+            // p = 0 along the lid, dp/dn = 0 on the other walls
+            PressureBc::Lid => {
+                poisson::spectral(p, b, dx, dy, Boundary::Neumann, Boundary::NeumannDirichlet)
+            }
+            // Pure Neumann solve, whose level is then fixed
+            bc => {
+                poisson::spectral(p, b, dx, dy, Boundary::Neumann, Boundary::Neumann);
+                let level = match bc {
+                    PressureBc::Reference(j, i) => p[[j, i]],
+                    _ => p.mean().unwrap(),
+                };
+                p.mapv_inplace(|x| x - level);
+            }
+        },
+    }
+
+    assign!(
+        u[1..-1, 1..-1] =  i!(un[1..-1, 1..-1])
+            - dt * i!(cu[1..-1, 1..-1])
+            - dt / (2. * rho * dx) * (i!(p[1..-1, 2..]) - i!(p[1..-1, ..-2]))
+            + explicit * nu * (dt / dx.powi(2)
+                * (i!(un[1..-1, 2..]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[1..-1, ..-2]))
+                + dt / dy.powi(2)
+                    * (i!(un[2.., 1..-1]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[..-2, 1..-1])))
+    );
+    assign!(
+        v[1..-1, 1..-1] = i!(vn[1..-1, 1..-1])
+            - dt * i!(cv[1..-1, 1..-1])
+            - dt / (2. * rho * dy) * (i!(p[2.., 1..-1]) - i!(p[..-2, 1..-1]))
+            + explicit * nu * (dt / dx.powi(2)
+                * (i!(vn[1..-1, 2..]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[1..-1, ..-2]))
+                + dt / dy.powi(2)
+                    * (i!(vn[2.., 1..-1]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[..-2, 1..-1])))
+    );
+
+    if let Some((su, sv)) = source {
+        add!(u[1..-1, 1..-1] += dt * i!(su[1..-1, 1..-1]));
+        add!(v[1..-1, 1..-1] += dt * i!(sv[1..-1, 1..-1]));
+    }
+
+    fill!(u[0, ..] = 0.);
+    fill!(u[-1, ..] = options.lid);
+    fill!(u[.., 0] = 0.);
+    fill!(u[.., -1] = 0.);
+    fill!(v[0, ..] = 0.);
+    fill!(v[-1, ..] = 0.);
+    fill!(v[.., 0] = 0.);
+    fill!(v[.., -1] = 0.);
 
-        fill!(u[0, ..] = 0.);
-        fill!(u[-1, ..] = 1.);
-        fill!(u[.., 0] = 0.);
-        fill!(u[.., -1] = 0.);
-        fill!(v[0, ..] = 0.);
-        fill!(v[-1, ..] = 0.);
-        fill!(v[.., 0] = 0.);
-        fill!(v[.., -1] = 0.);
+    if let Viscous::Implicit(method) = options.viscous {
+        // (1 - theta nu dt lap) u' = u*, with the boundary values above held
+        let r = method.theta() * nu * dt;
+        *u = helmholtz(u, r / dx.powi(2), r / dy.powi(2), false);
+        *v = helmholtz(v, r / dx.powi(2), r / dy.powi(2), false);
     }
 }
 
@@ -107,7 +419,14 @@ fn set_b(
     );
 }
 
-fn set_pressure_poisson(p: &mut Array2<f64>, b: &Array2<f64>, dx: f64, dy: f64, nit: usize) {
+fn set_pressure_poisson(
+    p: &mut Array2<f64>,
+    b: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    nit: usize,
+    bc: PressureBc,
+) {
     for _ in 0..nit {
         let pn = p.clone();
 
@@ -117,5 +436,31 @@ fn set_pressure_poisson(p: &mut Array2<f64>, b: &Array2<f64>, dx: f64, dy: f64,
                 / (2. * (dx.powi(2) + dy.powi(2)))
                 - dx.powi(2) * dy.powi(2) / (2. * (dx.powi(2) + dy.powi(2))) * i!(b[1..-1, 1..-1])
         );
+
+        set_pressure_boundary_conditions(p, bc);
+    }
+}
+
+fn set_pressure_boundary_conditions(p: &mut Array2<f64>, bc: PressureBc) {
+    let pn = i!(p[.., -2]).to_owned();
+    assign!(p[.., -1] = pn); // dp/dx = 0 @ x = 2
+    let pn = i!(p[1, ..]).to_owned();
+    assign!(p[0, ..] = pn); // dp/dy = 0 @ y = 0
+    let pn = i!(p[.., 1]).to_owned();
+    assign!(p[.., 0] = pn); // dp/dx = 0 @ x = 0
+    match bc {
+        PressureBc::Lid => fill!(p[-1, ..] = 0.), // p = 0 @ y = 2
+        PressureBc::Reference(j, i) => {
+            let pn = i!(p[-2, ..]).to_owned();
+            assign!(p[-1, ..] = pn); // dp/dy = 0 @ y = 2
+            let level = p[[j, i]];
+            p.mapv_inplace(|x| x - level);
+        }
+        PressureBc::ZeroMean => {
+            let pn = i!(p[-2, ..]).to_owned();
+            assign!(p[-1, ..] = pn); // dp/dy = 0 @ y = 2
+            let level = p.mean().unwrap();
+            p.mapv_inplace(|x| x - level);
+        }
     }
 }
@@ -1,7 +1,33 @@
 // https://github.com/barbagroup/CFDPython/blob/master/lessons/15_Step_12.ipynb
 use ndarray::prelude::*;
+use std::io::{self, Write};
 
-use crate::macros::{assign, fill, i};
+use crate::implicit::{helmholtz, Viscous};
+use crate::macros::{add, assign, fill, i};
+use crate::monitor::Monitor;
+use crate::norm::Norms;
+use crate::obstacle::{self, Forcing};
+use crate::poisson::{self, PressureSolver};
+use crate::projection::{self, Case};
+use crate::scalar;
+use crate::upwind::{convection, Upwinding};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub pressure: PressureSolver,
+    pub convection: Upwinding,
+    pub viscous: Viscous,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            pressure: PressureSolver::Jacobi(50),
+            convection: Upwinding::Backward,
+            viscous: Viscous::Explicit,
+        };
+    }
+}
 
 pub fn default() -> (
     Array2<f64>,
@@ -49,88 +75,304 @@ pub fn solve(
     f: f64,
     eps: f64,
 ) {
-    let mut udiff = 1.;
-    let mut stepcount = 0;
-    while udiff > eps {
-        let un = u.clone();
-        let vn = v.clone();
-        let b = make_b(&u, &v, dx, dy, dt, rho);
-        set_pressure_poisson(p, b, nit, dx, dy);
+    let options = Options {
+        pressure: PressureSolver::Jacobi(nit),
+        ..Default::default()
+    };
+    solve_with(u, v, p, dx, dy, dt, rho, nu, f, eps, usize::MAX, &options);
+}
 
-        assign!(
-            u[1..-1, 1..-1] = i!(un[1..-1, 1..-1])
-                - i!(un[1..-1, 1..-1]) * dt / dx * (i!(un[1..-1, 1..-1]) - i!(un[1..-1, 0..-2]))
-                - i!(vn[1..-1, 1..-1]) * dt / dy * (i!(un[1..-1, 1..-1]) - i!(un[0..-2, 1..-1]))
-                - dt / (2. * rho * dx) * (i!(p[1..-1, 2..]) - i!(p[1..-1, 0..-2]))
-                + nu * (dt / dx.powi(2)
-                    * (i!(un[1..-1, 2..]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[1..-1, 0..-2]))
-                    + dt / dy.powi(2)
-                        * (i!(un[2.., 1..-1]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[0..-2, 1..-1])))
-                + f * dt
-        );
-        assign!(
-            v[1..-1, 1..-1] = i!(vn[1..-1, 1..-1])
-                - i!(un[1..-1, 1..-1]) * dt / dx * (i!(vn[1..-1, 1..-1]) - i!(vn[1..-1, 0..-2]))
-                - i!(vn[1..-1, 1..-1]) * dt / dy * (i!(vn[1..-1, 1..-1]) - i!(vn[0..-2, 1..-1]))
-                - dt / (2. * rho * dy) * (i!(p[2.., 1..-1]) - i!(p[0..-2, 1..-1]))
-                + nu * (dt / dx.powi(2)
-                    * (i!(vn[1..-1, 2..]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[1..-1, 0..-2]))
-                    + dt / dy.powi(2)
-                        * (i!(vn[2.., 1..-1]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[0..-2, 1..-1])))
-        );
+// Marches `step` until the relative change in the sum of u is at most eps, and returns the
+// number of steps, or None when `max_steps` pass first or the march blows up
+fn march<F>(u: &mut Array2<f64>, eps: f64, max_steps: usize, mut step: F) -> Option<usize>
+where
+    F: FnMut(&mut Array2<f64>, usize),
+{
+    for stepcount in 0..max_steps {
+        let sum = u.sum();
+        step(u, stepcount);
 
-        // Periodic BC u @ x = 2
-        assign!(
-            u[1..-1, -1] = i!(un[1..-1, -1])
-                - i!(un[1..-1, -1]) * dt / dx * (i!(un[1..-1, -1]) - i!(un[1..-1, -2]))
-                - i!(vn[1..-1, -1]) * dt / dy * (i!(un[1..-1, -1]) - i!(un[0..-2, -1]))
-                - dt / (2. * rho * dx) * (i!(p[1..-1, 0]) - i!(p[1..-1, -2]))
-                + nu * (dt / dx.powi(2)
-                    * (i!(un[1..-1, 0]) - 2. * i!(un[1..-1,-1]) + i!(un[1..-1, -2]))
-                    + dt / dy.powi(2) * (i!(un[2.., -1]) - 2. * i!(un[1..-1, -1]) + i!(un[0..-2, -1])))
-                + f * dt
-        );
-        // Periodic BC u @ x = 0
-        assign!(
-            u[1..-1, 0] = i!(un[1..-1, 0])
-                - i!(un[1..-1, 0]) * dt / dx * (i!(un[1..-1, 0]) - i!(un[1..-1, -1]))
-                - i!(vn[1..-1, 0]) * dt / dy * (i!(un[1..-1, 0]) - i!(un[0..-2, 0]))
-                - dt / (2. * rho * dx) * (i!(p[1..-1, 1]) - i!(p[1..-1, -1]))
-                + nu * (dt / dx.powi(2)
-                    * (i!(un[1..-1, 1]) - 2. * i!(un[1..-1, 0]) + i!(un[1..-1, -1]))
-                    + dt / dy.powi(2) * (i!(un[2.., 0]) - 2. * i!(un[1..-1, 0]) + i!(un[0..-2, 0])))
-                + f * dt
-        );
-        // Periodic BC v @ x = 2
-        assign!(
-            v[1..-1, -1] = i!(vn[1..-1, -1])
-                - i!(un[1..-1, -1]) * dt / dx * (i!(vn[1..-1, -1]) - i!(vn[1..-1, -2]))
-                - i!(vn[1..-1, -1]) * dt / dy * (i!(vn[1..-1, -1]) - i!(vn[0..-2, -1]))
-                - dt / (2. * rho * dy) * (i!(p[2.., -1]) - i!(p[0..-2, -1]))
-                + nu * (dt / dx.powi(2)
-                    * (i!(vn[1..-1, 0]) - 2. * i!(vn[1..-1, -1]) + i!(vn[1..-1, -2]))
-                    + dt / dy.powi(2) * (i!(vn[2.., -1]) - 2. * i!(vn[1..-1, -1]) + i!(vn[0..-2, -1])))
-        );
-        // Periodic BC v @ x = 0
-        assign!(
-            v[1..-1, 0] = i!(vn[1..-1, 0])
-                - i!(un[1..-1, 0]) * dt / dx * (i!(vn[1..-1, 0]) - i!(vn[1..-1, -1]))
-                - i!(vn[1..-1, 0]) * dt / dy * (i!(vn[1..-1, 0]) - i!(vn[0..-2, 0]))
-                - dt / (2. * rho * dy) * (i!(p[2.., 0]) - i!(p[0..-2, 0]))
-                + nu * (dt / dx.powi(2)
-                    * (i!(vn[1..-1, 1]) - 2. * i!(vn[1..-1, 0]) + i!(vn[1..-1, -1]))
-                    + dt / dy.powi(2) * (i!(vn[2.., 0]) - 2. * i!(vn[1..-1, 0]) + i!(vn[0..-2, 0])))
-        );
-        // Wall BC: u,v = 0 @ y = 0, 2
-        fill!(u[0, ..] = 0.);
-        fill!(u[-1, ..] = 0.);
-        fill!(v[0, ..] = 0.);
-        fill!(v[-1, ..] = 0.);
-
-        udiff = (u.sum() - un.sum()).abs() / u.sum();
-        stepcount += 1;
+        let udiff = (u.sum() - sum).abs() / u.sum();
+        if !udiff.is_finite() {
+            return None;
+        }
+        if udiff <= eps {
+            return Some(stepcount + 1);
+        }
+    }
+    return None;
+}
+
+pub fn solve_with(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    f: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+) -> Option<usize> {
+    return march(u, eps, max_steps, |u, _| {
+        step(u, v, p, dx, dy, dt, rho, nu, f, options, None)
+    });
+}
+
+// Returns None as `solve_with` does
+pub fn solve_monitored<W: Write>(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    f: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+    monitor: &mut Monitor<W>,
+) -> io::Result<Option<usize>> {
+    let mut udiff = f64::NAN;
+    let stepcount = monitor.march(
+        &mut (u, v, p),
+        max_steps,
+        dt,
+        dx,
+        dy,
+        true,
+        |(u, v, p)| {
+            let sum = u.sum();
+            step(u, v, p, dx, dy, dt, rho, nu, f, options, None);
+
+            udiff = (u.sum() - sum).abs() / u.sum();
+            return udiff > eps;
+        },
+        |(u, v, p)| vec![("u", u.view()), ("v", v.view()), ("p", p.view())],
+    )?;
+    return Ok((udiff <= eps).then_some(stepcount));
+}
+
+// Fractional steps instead of the lesson's pressure Poisson update; returns the norms of
+// the divergence after every step, or None as `solve_with` does
+pub fn solve_projection(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    f: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &projection::Options,
+) -> Option<Vec<Norms>> {
+    let mut divergence = Vec::new();
+    march(u, eps, max_steps, |u, _| {
+        divergence.push(projection::step(
+            u,
+            v,
+            p,
+            dx,
+            dy,
+            dt,
+            rho,
+            nu,
+            Case::Channel(f),
+            options,
+        ))
+    })?;
+    return Some(divergence);
+}
+
+// nt steps of `solve_with` carrying the passive scalar phi, which each step advances with
+// the velocity it starts from
+pub fn solve_scalar(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    phi: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    f: f64,
+    kappa: f64,
+    options: &Options,
+    scalar: &scalar::Options,
+    source: Option<&Array2<f64>>,
+) {
+    for _ in 0..nt {
+        scalar::step(phi, u, v, dx, dy, dt, kappa, true, scalar, source);
+        step(u, v, p, dx, dy, dt, rho, nu, f, options, None);
+    }
+}
+
+// `solve_with` around the solid body of fractions `alpha` (see obstacle), which repeats
+// with the period of the channel. The flow around a body varies along x too, so the
+// default time step, nu dt / dx^2 = 0.4, needs implicit viscous terms.
+pub fn solve_masked(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    f: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+    alpha: &Array2<f64>,
+    forcing: Forcing,
+) -> Option<usize> {
+    return march(u, eps, max_steps, |u, _| {
+        step(u, v, p, dx, dy, dt, rho, nu, f, options, None);
+        obstacle::force(u, v, alpha, forcing);
+    });
+}
+
+// `source(t)` is added to the right-hand sides of the momentum equations of every step
+pub fn solve_forced<F>(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    f: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+    source: F,
+) -> Option<usize>
+where
+    F: Fn(f64) -> (Array2<f64>, Array2<f64>),
+{
+    return march(u, eps, max_steps, |u, stepcount| {
+        let s = source(stepcount as f64 * dt);
+        step(u, v, p, dx, dy, dt, rho, nu, f, options, Some(&s));
+    });
+}
+
+fn step(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    f: f64,
+    options: &Options,
+    source: Option<&(Array2<f64>, Array2<f64>)>,
+) {
+    let un = u.clone();
+    let vn = v.clone();
+    // Weight of the viscous terms at the old time level
+    let explicit = match options.viscous {
+        Viscous::Explicit => 1.,
+        Viscous::Implicit(method) => 1. - method.theta(),
+    };
+    let cu = convection(&un, &un, &vn, dx, dy, options.convection, true);
+    let cv = convection(&vn, &un, &vn, dx, dy, options.convection, true);
+    let mut b = make_b(u, v, dx, dy, dt, rho);
+    if let Some((su, sv)) = source {
+        // The divergence of the momentum source enters the pressure equation
+        add!(b[1..-1, ..] += rho * divergence(su, sv, dx, dy));
+    }
+    match options.pressure {
+        PressureSolver::Jacobi(nit) => set_pressure_poisson(p, b, nit, dx, dy),
+        PressureSolver::Spectral => poisson::channel(p, &b, dx, dy),
+    }
+
+    assign!(
+        u[1..-1, 1..-1] = i!(un[1..-1, 1..-1])
+            - dt * i!(cu[1..-1, 1..-1])
+            - dt / (2. * rho * dx) * (i!(p[1..-1, 2..]) - i!(p[1..-1, 0..-2]))
+            + explicit * nu * (dt / dx.powi(2)
+                * (i!(un[1..-1, 2..]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[1..-1, 0..-2]))
+                + dt / dy.powi(2)
+                    * (i!(un[2.., 1..-1]) - 2. * i!(un[1..-1, 1..-1]) + i!(un[0..-2, 1..-1])))
+            + f * dt
+    );
+    assign!(
+        v[1..-1, 1..-1] = i!(vn[1..-1, 1..-1])
+            - dt * i!(cv[1..-1, 1..-1])
+            - dt / (2. * rho * dy) * (i!(p[2.., 1..-1]) - i!(p[0..-2, 1..-1]))
+            + explicit * nu * (dt / dx.powi(2)
+                * (i!(vn[1..-1, 2..]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[1..-1, 0..-2]))
+                + dt / dy.powi(2)
+                    * (i!(vn[2.., 1..-1]) - 2. * i!(vn[1..-1, 1..-1]) + i!(vn[0..-2, 1..-1])))
+    );
+
+    // Periodic BC u @ x = 2
+    assign!(
+        u[1..-1, -1] = i!(un[1..-1, -1])
+            - dt * i!(cu[1..-1, -1])
+            - dt / (2. * rho * dx) * (i!(p[1..-1, 0]) - i!(p[1..-1, -2]))
+            + explicit * nu * (dt / dx.powi(2)
+                * (i!(un[1..-1, 0]) - 2. * i!(un[1..-1,-1]) + i!(un[1..-1, -2]))
+                + dt / dy.powi(2) * (i!(un[2.., -1]) - 2. * i!(un[1..-1, -1]) + i!(un[0..-2, -1])))
+            + f * dt
+    );
+    // Periodic BC u @ x = 0
+    assign!(
+        u[1..-1, 0] = i!(un[1..-1, 0])
+            - dt * i!(cu[1..-1, 0])
+            - dt / (2. * rho * dx) * (i!(p[1..-1, 1]) - i!(p[1..-1, -1]))
+            + explicit * nu * (dt / dx.powi(2)
+                * (i!(un[1..-1, 1]) - 2. * i!(un[1..-1, 0]) + i!(un[1..-1, -1]))
+                + dt / dy.powi(2) * (i!(un[2.., 0]) - 2. * i!(un[1..-1, 0]) + i!(un[0..-2, 0])))
+            + f * dt
+    );
+    // Periodic BC v @ x = 2
+    assign!(
+        v[1..-1, -1] = i!(vn[1..-1, -1])
+            - dt * i!(cv[1..-1, -1])
+            - dt / (2. * rho * dy) * (i!(p[2.., -1]) - i!(p[0..-2, -1]))
+            + explicit * nu * (dt / dx.powi(2)
+                * (i!(vn[1..-1, 0]) - 2. * i!(vn[1..-1, -1]) + i!(vn[1..-1, -2]))
+                + dt / dy.powi(2) * (i!(vn[2.., -1]) - 2. * i!(vn[1..-1, -1]) + i!(vn[0..-2, -1])))
+    );
+    // Periodic BC v @ x = 0
+    assign!(
+        v[1..-1, 0] = i!(vn[1..-1, 0])
+            - dt * i!(cv[1..-1, 0])
+            - dt / (2. * rho * dy) * (i!(p[2.., 0]) - i!(p[0..-2, 0]))
+            + explicit * nu * (dt / dx.powi(2)
+                * (i!(vn[1..-1, 1]) - 2. * i!(vn[1..-1, 0]) + i!(vn[1..-1, -1]))
+                + dt / dy.powi(2) * (i!(vn[2.., 0]) - 2. * i!(vn[1..-1, 0]) + i!(vn[0..-2, 0])))
+    );
+    if let Some((su, sv)) = source {
+        add!(u[1..-1, ..] += dt * i!(su[1..-1, ..]));
+        add!(v[1..-1, ..] += dt * i!(sv[1..-1, ..]));
+    }
+    // Wall BC: u,v = 0 @ y = 0, 2
+    fill!(u[0, ..] = 0.);
+    fill!(u[-1, ..] = 0.);
+    fill!(v[0, ..] = 0.);
+    fill!(v[-1, ..] = 0.);
+
+    if let Viscous::Implicit(method) = options.viscous {
+        // (1 - theta nu dt lap) u' = u*, with the boundary values above held
+        let r = method.theta() * nu * dt;
+        *u = helmholtz(u, r / dx.powi(2), r / dy.powi(2), true);
+        *v = helmholtz(v, r / dx.powi(2), r / dy.powi(2), true);
     }
-    println!("{}", stepcount);
 }
 
 fn make_b(u: &Array2<f64>, v: &Array2<f64>, dx: f64, dy: f64, dt: f64, rho: f64) -> Array2<f64> {
@@ -204,3 +446,12 @@ fn set_pressure_poisson(p: &mut Array2<f64>, b: Array2<f64>, nit: usize, dx: f64
         assign!(p[-1, ..] = pn); // dp/dy = 0 @ y = 2
     }
 }
+
+// Central-difference divergence on the interior rows, periodic in x
+fn divergence(su: &Array2<f64>, sv: &Array2<f64>, dx: f64, dy: f64) -> Array2<f64> {
+    let (ny, nx) = su.dim();
+    return Array::from_shape_fn((ny - 2, nx), |(j, i)| {
+        (su[[j + 1, (i + 1) % nx]] - su[[j + 1, (i + nx - 1) % nx]]) / (2. * dx)
+            + (sv[[j + 2, i]] - sv[[j, i]]) / (2. * dy)
+    });
+}
@@ -0,0 +1,49 @@
+// 3D counterparts of the 2D lessons. Fields are Array3 indexed [k, j, i] for the node at
+// (x, y, z) = (i dx, j dy, k dz), so that [j, i] keeps its meaning in every z plane, and
+// the initial and boundary conditions of each lesson carry over to the third direction.
+use ndarray::prelude::*;
+
+use crate::macros::{assign, fill, i};
+
+pub mod x7;
+pub mod x8;
+pub mod x9;
+pub mod xa;
+pub mod xc;
+pub mod xd;
+pub mod xe;
+
+// The square-function IC of the lessons: 2 on [0.5, 1]^3 and 1 elsewhere
+pub(crate) fn hat((nz, ny, nx): (usize, usize, usize), dx: f64, dy: f64, dz: f64) -> Array3<f64> {
+    let mut u = Array::ones((nz, ny, nx));
+    let range = |h: f64| (0.5 / h) as usize..(1. / h + 1.) as usize;
+    fill!(u[range(dz), range(dy), range(dx)] = 2.);
+    return u;
+}
+
+// The same value on all six faces
+pub(crate) fn set_faces(u: &mut Array3<f64>, value: f64) {
+    fill!(u[0, .., ..] = value);
+    fill!(u[-1, .., ..] = value);
+    fill!(u[.., 0, ..] = value);
+    fill!(u[.., -1, ..] = value);
+    fill!(u[.., .., 0] = value);
+    fill!(u[.., .., -1] = value);
+}
+
+// Seven-point Laplacian at the interior nodes, zero on the faces
+pub(crate) fn laplacian(u: &Array3<f64>, dx: f64, dy: f64, dz: f64) -> Array3<f64> {
+    let mut l = Array::zeros(u.raw_dim());
+    assign!(
+        l[1..-1, 1..-1, 1..-1] = (i!(u[1..-1, 1..-1, 2..]) - 2. * i!(u[1..-1, 1..-1, 1..-1])
+            + i!(u[1..-1, 1..-1, ..-2]))
+            / dx.powi(2)
+            + (i!(u[1..-1, 2.., 1..-1]) - 2. * i!(u[1..-1, 1..-1, 1..-1])
+                + i!(u[1..-1, ..-2, 1..-1]))
+                / dy.powi(2)
+            + (i!(u[2.., 1..-1, 1..-1]) - 2. * i!(u[1..-1, 1..-1, 1..-1])
+                + i!(u[..-2, 1..-1, 1..-1]))
+                / dz.powi(2)
+    );
+    return l;
+}
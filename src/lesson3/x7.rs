@@ -0,0 +1,39 @@
+// Linear convection in 3D, the counterpart of lesson::x7
+use ndarray::prelude::*;
+
+use super::{hat, set_faces};
+use crate::macros::{assign, i};
+
+pub fn default() -> (Array3<f64>, usize, f64, f64, f64, f64, f64) {
+    let nx = 31;
+    let ny = 31;
+    let nz = 31;
+    let nt = 50;
+
+    let c = 1.;
+    let sigma = 0.2;
+
+    let dx = 2. / (nx as f64 - 1.);
+    let dy = 2. / (ny as f64 - 1.);
+    let dz = 2. / (nz as f64 - 1.);
+    let dt = sigma * dx;
+
+    let u = hat((nz, ny, nx), dx, dy, dz);
+
+    return (u, nt, dx, dy, dz, dt, c);
+}
+
+pub fn solve(u: &mut Array3<f64>, nt: usize, dx: f64, dy: f64, dz: f64, dt: f64, c: f64) {
+    for _ in 0..nt + 1 {
+        let un = u.clone();
+
+        assign!(
+            u[1.., 1.., 1..] = i!(un[1.., 1.., 1..])
+                - (c * dt / dx * (i!(un[1.., 1.., 1..]) - i!(un[1.., 1.., ..-1])))
+                - (c * dt / dy * (i!(un[1.., 1.., 1..]) - i!(un[1.., ..-1, 1..])))
+                - (c * dt / dz * (i!(un[1.., 1.., 1..]) - i!(un[..-1, 1.., 1..])))
+        );
+
+        set_faces(u, 1.);
+    }
+}
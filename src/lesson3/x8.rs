@@ -0,0 +1,69 @@
+// Nonlinear convection in 3D, the counterpart of lesson::x8
+use ndarray::prelude::*;
+
+use super::{hat, set_faces};
+use crate::upwind::{convection3, Upwinding};
+
+pub fn default() -> (
+    Array3<f64>,
+    Array3<f64>,
+    Array3<f64>,
+    usize,
+    f64,
+    f64,
+    f64,
+    f64,
+) {
+    let nx = 31;
+    let ny = 31;
+    let nz = 31;
+    let nt = 40;
+
+    let sigma = 0.2;
+
+    let dx = 2. / (nx as f64 - 1.);
+    let dy = 2. / (ny as f64 - 1.);
+    let dz = 2. / (nz as f64 - 1.);
+    let dt = sigma * dx;
+
+    let u = hat((nz, ny, nx), dx, dy, dz);
+    let v = u.clone();
+    let w = u.clone();
+
+    return (u, v, w, nt, dx, dy, dz, dt);
+}
+
+pub fn solve(
+    u: &mut Array3<f64>,
+    v: &mut Array3<f64>,
+    w: &mut Array3<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    dt: f64,
+) {
+    solve_with(u, v, w, nt, dx, dy, dz, dt, Upwinding::Backward);
+}
+
+pub fn solve_with(
+    u: &mut Array3<f64>,
+    v: &mut Array3<f64>,
+    w: &mut Array3<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    dt: f64,
+    upwinding: Upwinding,
+) {
+    for _ in 0..nt + 1 {
+        let un = u.clone();
+        let vn = v.clone();
+        let wn = w.clone();
+        for (q, qn) in [(&mut *u, &un), (&mut *v, &vn), (&mut *w, &wn)] {
+            *q -= &(dt * convection3(qn, &un, &vn, &wn, dx, dy, dz, upwinding));
+            set_faces(q, 1.);
+        }
+    }
+}
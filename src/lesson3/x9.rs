@@ -0,0 +1,33 @@
+// Diffusion in 3D, the counterpart of lesson::x9
+use ndarray::prelude::*;
+
+use super::{hat, laplacian, set_faces};
+
+pub fn default() -> (Array3<f64>, usize, f64, f64, f64, f64, f64) {
+    let nx = 31;
+    let ny = 31;
+    let nz = 31;
+    let nt = 17;
+
+    let nu = 0.05;
+    // 1/6 at most with the extra direction
+    let sigma = 0.15;
+
+    let dx = 2. / (nx as f64 - 1.);
+    let dy = 2. / (ny as f64 - 1.);
+    let dz = 2. / (nz as f64 - 1.);
+    let dt = sigma * dx * dy / nu;
+
+    let u = hat((nz, ny, nx), dx, dy, dz);
+
+    return (u, nt, dx, dy, dz, dt, nu);
+}
+
+pub fn solve(u: &mut Array3<f64>, nt: usize, dx: f64, dy: f64, dz: f64, dt: f64, nu: f64) {
+    for _ in 0..nt + 1 {
+        let l = laplacian(u, dx, dy, dz);
+        *u += &(nu * dt * l);
+
+        set_faces(u, 1.);
+    }
+}
@@ -0,0 +1,59 @@
+// Laplace equation in 3D, the counterpart of lesson::xc: its boundary conditions hold
+// along z, and the z faces are insulated too
+use ndarray::prelude::*;
+
+use crate::macros::{assign, fill, i};
+
+pub fn default() -> (Array3<f64>, Array1<f64>, f64, f64, f64, f64) {
+    let nx = 31;
+    let ny = 31;
+    let nz = 31;
+
+    let eps = 1e-4;
+
+    let dx = 2. / (nx as f64 - 1.);
+    let dy = 2. / (ny as f64 - 1.);
+    let dz = 2. / (nz as f64 - 1.);
+
+    let y = Array::linspace(0., 1., nx);
+    let mut p = Array::zeros((nz, ny, nx));
+    set_boundary_conditions(&mut p, &y);
+
+    return (p, y, dx, dy, dz, eps);
+}
+
+pub fn solve(p: &mut Array3<f64>, y: Array1<f64>, dx: f64, dy: f64, dz: f64, eps: f64) {
+    let (ax, ay, az) = (
+        dy.powi(2) * dz.powi(2),
+        dx.powi(2) * dz.powi(2),
+        dx.powi(2) * dy.powi(2),
+    );
+    let mut norm = 1.;
+    while norm > eps {
+        let pn = p.clone();
+
+        assign!(
+            p[1..-1, 1..-1, 1..-1] = (ax * (i!(pn[1..-1, 1..-1, 2..]) + i!(pn[1..-1, 1..-1, ..-2]))
+                + ay * (i!(pn[1..-1, 2.., 1..-1]) + i!(pn[1..-1, ..-2, 1..-1]))
+                + az * (i!(pn[2.., 1..-1, 1..-1]) + i!(pn[..-2, 1..-1, 1..-1])))
+                / (2. * (ax + ay + az))
+        );
+
+        set_boundary_conditions(p, &y);
+
+        norm = (p.mapv(f64::abs) - pn.mapv(f64::abs)).sum().abs() / pn.mapv(f64::abs).sum();
+    }
+}
+
+fn set_boundary_conditions(p: &mut Array3<f64>, y: &Array1<f64>) {
+    fill!(p[.., .., 0] = 0.); // p = 0 @ x = 0
+    assign!(p[.., .., -1] = y); // p = y @ x = 2
+    let pn = i!(p[.., 1, ..]).to_owned();
+    assign!(p[.., 0, ..] = pn); // dp/dy = 0 @ y = 0
+    let pn = i!(p[.., -2, ..]).to_owned();
+    assign!(p[.., -1, ..] = pn); // dp/dy = 0 @ y = 1
+    let pn = i!(p[1, .., ..]).to_owned();
+    assign!(p[0, .., ..] = pn); // dp/dz = 0 @ z = 0
+    let pn = i!(p[-2, .., ..]).to_owned();
+    assign!(p[-1, .., ..] = pn); // dp/dz = 0 @ z = 1
+}
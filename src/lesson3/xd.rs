@@ -0,0 +1,44 @@
+// Poisson equation in 3D, the counterpart of lesson::xd
+use ndarray::prelude::*;
+
+use super::set_faces;
+use crate::macros::{assign, i};
+
+pub fn default() -> (Array3<f64>, Array3<f64>, usize, f64, f64, f64) {
+    let nx = 50;
+    let ny = 50;
+    let nz = 50;
+    let nt = 100;
+
+    let dx = 2. / (nx as f64 - 1.);
+    let dy = 1. / (ny as f64 - 1.);
+    let dz = 1. / (nz as f64 - 1.);
+
+    let p = Array::zeros((nz, ny, nx));
+    let mut b = Array::zeros((nz, ny, nx));
+    b[[nz / 4, ny / 4, nx / 4]] = 100.;
+    b[[3 * nz / 4, 3 * ny / 4, 3 * nx / 4]] = -100.;
+
+    return (p, b, nt, dx, dy, dz);
+}
+
+pub fn solve(p: &mut Array3<f64>, b: Array3<f64>, nt: usize, dx: f64, dy: f64, dz: f64) {
+    let (ax, ay, az) = (
+        dy.powi(2) * dz.powi(2),
+        dx.powi(2) * dz.powi(2),
+        dx.powi(2) * dy.powi(2),
+    );
+    for _ in 0..nt + 1 {
+        let pn = p.clone();
+
+        assign!(
+            p[1..-1, 1..-1, 1..-1] = (ax * (i!(pn[1..-1, 1..-1, 2..]) + i!(pn[1..-1, 1..-1, ..-2]))
+                + ay * (i!(pn[1..-1, 2.., 1..-1]) + i!(pn[1..-1, ..-2, 1..-1]))
+                + az * (i!(pn[2.., 1..-1, 1..-1]) + i!(pn[..-2, 1..-1, 1..-1]))
+                - i!(b[1..-1, 1..-1, 1..-1]) * dx.powi(2) * dy.powi(2) * dz.powi(2))
+                / (2. * (ax + ay + az))
+        );
+
+        set_faces(p, 0.);
+    }
+}
@@ -0,0 +1,259 @@
+// Cavity flow in 3D, the counterpart of lesson::xe: the lid y = 2 slides along x between
+// the end walls z = 0 and z = 2, and the pressure keeps the lesson's conditions, p = 0
+// on the lid and dp/dn = 0 on the other walls.
+//
+// The options are a subset of the 2D ones on purpose. The pressure is always relaxed by
+// Jacobi sweeps under those conditions and the viscous terms are always explicit: the
+// spectral solver of poisson, the other PressureBc choices and the implicit viscous terms
+// of implicit all work on 2D fields, and so does monitor. The channel flow of lesson::xf
+// has no 3D counterpart.
+use ndarray::prelude::*;
+
+use super::{laplacian, set_faces};
+use crate::macros::{assign, fill, i};
+use crate::upwind::{convection3, Upwinding};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    // Jacobi sweeps of the pressure Poisson equation per step
+    pub nit: usize,
+    pub convection: Upwinding,
+    pub lid: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            nit: 50,
+            convection: Upwinding::Backward,
+            lid: 1.,
+        };
+    }
+}
+
+pub fn default() -> (
+    Array3<f64>,
+    Array3<f64>,
+    Array3<f64>,
+    Array3<f64>,
+    usize,
+    usize,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+) {
+    let nx = 21;
+    let ny = 21;
+    let nz = 21;
+    let nt = 500;
+    let nit = 50;
+    let dt = 0.001;
+
+    let rho = 1.;
+    let nu = 0.1;
+
+    let dx = 2. / (nx as f64 - 1.);
+    let dy = 2. / (ny as f64 - 1.);
+    let dz = 2. / (nz as f64 - 1.);
+
+    let u = Array::zeros((nz, ny, nx));
+    let v = Array::zeros((nz, ny, nx));
+    let w = Array::zeros((nz, ny, nx));
+    let p = Array::zeros((nz, ny, nx));
+
+    return (u, v, w, p, nt, nit, dx, dy, dz, dt, rho, nu);
+}
+
+pub fn solve(
+    u: &mut Array3<f64>,
+    v: &mut Array3<f64>,
+    w: &mut Array3<f64>,
+    p: &mut Array3<f64>,
+    nt: usize,
+    nit: usize,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+) {
+    let options = Options {
+        nit,
+        ..Default::default()
+    };
+    solve_with(u, v, w, p, nt, dx, dy, dz, dt, rho, nu, &options);
+}
+
+pub fn solve_with(
+    u: &mut Array3<f64>,
+    v: &mut Array3<f64>,
+    w: &mut Array3<f64>,
+    p: &mut Array3<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    options: &Options,
+) {
+    for _ in 0..nt {
+        step(u, v, w, p, dx, dy, dz, dt, rho, nu, options);
+    }
+}
+
+// Marches until the largest velocity change per unit time falls below `eps`; returns the
+// number of steps, or None when `max_steps` pass first or the march blows up
+pub fn steady(
+    u: &mut Array3<f64>,
+    v: &mut Array3<f64>,
+    w: &mut Array3<f64>,
+    p: &mut Array3<f64>,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+) -> Option<usize> {
+    for stepcount in 1..=max_steps {
+        let (un, vn, wn) = (u.clone(), v.clone(), w.clone());
+        step(u, v, w, p, dx, dy, dz, dt, rho, nu, options);
+
+        let diff = (&*u - &un)
+            .iter()
+            .chain((&*v - &vn).iter())
+            .chain((&*w - &wn).iter())
+            .fold(0., |a: f64, x| a.max(x.abs()))
+            / dt;
+        // max skips NaN, so a blow-up shows in the sums
+        if !(diff + u.sum() + v.sum() + w.sum()).is_finite() {
+            return None;
+        }
+        if diff <= eps {
+            return Some(stepcount);
+        }
+    }
+    return None;
+}
+
+fn step(
+    u: &mut Array3<f64>,
+    v: &mut Array3<f64>,
+    w: &mut Array3<f64>,
+    p: &mut Array3<f64>,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    options: &Options,
+) {
+    let un = u.clone();
+    let vn = v.clone();
+    let wn = w.clone();
+    let b = make_b(&un, &vn, &wn, dx, dy, dz, dt, rho);
+    set_pressure_poisson(p, &b, dx, dy, dz, options.nit);
+
+    // Each component with the pressure gradient along its axis
+    for (q, qn, axis, h) in [
+        (&mut *u, &un, 2, dx),
+        (&mut *v, &vn, 1, dy),
+        (&mut *w, &wn, 0, dz),
+    ] {
+        let c = convection3(qn, &un, &vn, &wn, dx, dy, dz, options.convection);
+        let l = laplacian(qn, dx, dy, dz);
+        assign!(
+            q[1..-1, 1..-1, 1..-1] = i!(qn[1..-1, 1..-1, 1..-1])
+                + dt * (nu * i!(l[1..-1, 1..-1, 1..-1]) - i!(c[1..-1, 1..-1, 1..-1]))
+                - dt / rho * central(p, axis, h)
+        );
+    }
+
+    set_faces(u, 0.);
+    fill!(u[1..-1, -1, 1..-1] = options.lid);
+    set_faces(v, 0.);
+    set_faces(w, 0.);
+}
+
+// Central difference along `axis` (0, 1, 2 for z, y, x) at the interior nodes
+fn central(q: &Array3<f64>, axis: usize, h: f64) -> Array3<f64> {
+    return match axis {
+        0 => (i!(q[2.., 1..-1, 1..-1]) - i!(q[..-2, 1..-1, 1..-1])) / (2. * h),
+        1 => (i!(q[1..-1, 2.., 1..-1]) - i!(q[1..-1, ..-2, 1..-1])) / (2. * h),
+        _ => (i!(q[1..-1, 1..-1, 2..]) - i!(q[1..-1, 1..-1, ..-2])) / (2. * h),
+    };
+}
+
+// Right-hand side of the pressure Poisson equation at the interior nodes
+fn make_b(
+    u: &Array3<f64>,
+    v: &Array3<f64>,
+    w: &Array3<f64>,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    dt: f64,
+    rho: f64,
+) -> Array3<f64> {
+    let (ux, uy, uz) = (central(u, 2, dx), central(u, 1, dy), central(u, 0, dz));
+    let (vx, vy, vz) = (central(v, 2, dx), central(v, 1, dy), central(v, 0, dz));
+    let (wx, wy, wz) = (central(w, 2, dx), central(w, 1, dy), central(w, 0, dz));
+    return rho
+        * (1. / dt * (&ux + &vy + &wz)
+            - ux.mapv(|x| x.powi(2))
+            - vy.mapv(|x| x.powi(2))
+            - wz.mapv(|x| x.powi(2))
+            - 2. * (uy * vx + uz * wx + vz * wy));
+}
+
+fn set_pressure_poisson(
+    p: &mut Array3<f64>,
+    b: &Array3<f64>,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    nit: usize,
+) {
+    let (ax, ay, az) = (
+        dy.powi(2) * dz.powi(2),
+        dx.powi(2) * dz.powi(2),
+        dx.powi(2) * dy.powi(2),
+    );
+    for _ in 0..nit {
+        let pn = p.clone();
+
+        assign!(
+            p[1..-1, 1..-1, 1..-1] = (ax * (i!(pn[1..-1, 1..-1, 2..]) + i!(pn[1..-1, 1..-1, ..-2]))
+                + ay * (i!(pn[1..-1, 2.., 1..-1]) + i!(pn[1..-1, ..-2, 1..-1]))
+                + az * (i!(pn[2.., 1..-1, 1..-1]) + i!(pn[..-2, 1..-1, 1..-1]))
+                - dx.powi(2) * dy.powi(2) * dz.powi(2) * b)
+                / (2. * (ax + ay + az))
+        );
+
+        set_pressure_boundary_conditions(p);
+    }
+}
+
+fn set_pressure_boundary_conditions(p: &mut Array3<f64>) {
+    let pn = i!(p[.., .., -2]).to_owned();
+    assign!(p[.., .., -1] = pn); // dp/dx = 0 @ x = 2
+    let pn = i!(p[.., 1, ..]).to_owned();
+    assign!(p[.., 0, ..] = pn); // dp/dy = 0 @ y = 0
+    let pn = i!(p[.., .., 1]).to_owned();
+    assign!(p[.., .., 0] = pn); // dp/dx = 0 @ x = 0
+    let pn = i!(p[1, .., ..]).to_owned();
+    assign!(p[0, .., ..] = pn); // dp/dz = 0 @ z = 0
+    let pn = i!(p[-2, .., ..]).to_owned();
+    assign!(p[-1, .., ..] = pn); // dp/dz = 0 @ z = 2
+    fill!(p[.., -1, ..] = 0.); // p = 0 @ y = 2
+}
@@ -1,3 +1,28 @@
+pub mod advection;
+pub mod analytic;
+pub mod benchmark;
+pub mod boussinesq;
+pub mod channel;
+pub mod convergence;
+pub mod implicit;
+pub mod inflow;
 pub mod lesson;
+pub mod lesson3;
+pub mod mac;
+pub mod mms;
+pub mod monitor;
+pub mod norm;
+pub mod obstacle;
+pub mod poisson;
+pub mod post;
+pub mod projection;
+pub mod rk;
+pub mod scalar;
+pub mod simple;
+pub mod tvd;
+pub mod upwind;
+pub mod vorticity;
+pub mod vtk;
+pub mod weno;
 
 mod macros;
@@ -10,10 +10,17 @@ macro_rules! assign {
     }
 }
 
+macro_rules! add {
+    ($a:ident[$($x:expr),*] += $rhs:expr) => {{
+        let mut view = $a.slice_mut(s![$($x),*]);
+        view += &$rhs;
+    }}
+}
+
 macro_rules! fill {
     ($a:ident[$($x:expr),*] = $v:expr) => {
         $a.slice_mut(s![$($x),*]).fill($v)
     }
 }
 
-pub(crate) use {assign, fill, i};
+pub(crate) use {add, assign, fill, i};
@@ -0,0 +1,318 @@
+// Method of manufactured solutions for the 2D lessons. A smooth field f(t, x, y) chosen by
+// the user becomes the exact solution of a lesson once the residual it leaves in the PDE is
+// added as a source; the observed order of the error then verifies the discretisation.
+//
+// The fields must satisfy the boundary conditions the lessons hard-wire: u = v = 1 on the
+// edges for x9 and xa, p = 0 on the edges for xd, u = v = 0 and dp/dn = 0 on the walls of
+// xe, and periodicity over [0, 2] in x with u = v = 0 and dp/dy = 0 on the walls of xf.
+use ndarray::prelude::*;
+
+use crate::lesson::{x9, xa, xd, xe, xf};
+use crate::norm::{norms, Norms};
+use crate::poisson::PressureSolver;
+use crate::upwind::Upwinding;
+
+pub struct Field<F> {
+    f: F,
+}
+
+// Derivatives are taken by fourth-order central differences
+impl<F> Field<F>
+where
+    F: Fn(f64, f64, f64) -> f64,
+{
+    pub fn new(f: F) -> Self {
+        return Field { f };
+    }
+
+    pub fn at(&self, t: f64, x: f64, y: f64) -> f64 {
+        return (self.f)(t, x, y);
+    }
+
+    pub fn dt(&self, t: f64, x: f64, y: f64) -> f64 {
+        return d1(|s| (self.f)(s, x, y), t);
+    }
+
+    pub fn dx(&self, t: f64, x: f64, y: f64) -> f64 {
+        return d1(|s| (self.f)(t, s, y), x);
+    }
+
+    pub fn dy(&self, t: f64, x: f64, y: f64) -> f64 {
+        return d1(|s| (self.f)(t, x, s), y);
+    }
+
+    pub fn laplacian(&self, t: f64, x: f64, y: f64) -> f64 {
+        return d2(|s| (self.f)(t, s, y), x) + d2(|s| (self.f)(t, x, s), y);
+    }
+
+    // Node values at time t, with a[j, i] at (i dx, j dy)
+    pub fn sample(&self, t: f64, shape: (usize, usize), dx: f64, dy: f64) -> Array2<f64> {
+        return sample(|t, x, y| self.at(t, x, y), t, shape, dx, dy);
+    }
+}
+
+fn d1<G: Fn(f64) -> f64>(g: G, x: f64) -> f64 {
+    let h = 1e-3;
+    return (g(x - 2. * h) - 8. * g(x - h) + 8. * g(x + h) - g(x + 2. * h)) / (12. * h);
+}
+
+fn d2<G: Fn(f64) -> f64>(g: G, x: f64) -> f64 {
+    let h = 5e-3;
+    return (-g(x - 2. * h) + 16. * g(x - h) - 30. * g(x) + 16. * g(x + h) - g(x + 2. * h))
+        / (12. * h.powi(2));
+}
+
+pub fn sample<S>(s: S, t: f64, shape: (usize, usize), dx: f64, dy: f64) -> Array2<f64>
+where
+    S: Fn(f64, f64, f64) -> f64,
+{
+    return Array::from_shape_fn(shape, |(j, i)| s(t, i as f64 * dx, j as f64 * dy));
+}
+
+// u_t = nu lap(u) + s
+pub fn diffusion<'a, F>(u: &'a Field<F>, nu: f64) -> impl Fn(f64, f64, f64) -> f64 + 'a
+where
+    F: Fn(f64, f64, f64) -> f64,
+{
+    return move |t, x, y| u.dt(t, x, y) - nu * u.laplacian(t, x, y);
+}
+
+// u_t + u u_x + v u_y = nu lap(u) + s, and the same for v
+pub fn burgers<'a, F, G>(
+    u: &'a Field<F>,
+    v: &'a Field<G>,
+    nu: f64,
+) -> (
+    impl Fn(f64, f64, f64) -> f64 + 'a,
+    impl Fn(f64, f64, f64) -> f64 + 'a,
+)
+where
+    F: Fn(f64, f64, f64) -> f64,
+    G: Fn(f64, f64, f64) -> f64,
+{
+    let su = move |t, x, y| {
+        u.dt(t, x, y) + u.at(t, x, y) * u.dx(t, x, y) + v.at(t, x, y) * u.dy(t, x, y)
+            - nu * u.laplacian(t, x, y)
+    };
+    let sv = move |t, x, y| {
+        v.dt(t, x, y) + u.at(t, x, y) * v.dx(t, x, y) + v.at(t, x, y) * v.dy(t, x, y)
+            - nu * v.laplacian(t, x, y)
+    };
+    return (su, sv);
+}
+
+// lap(p) = b
+pub fn poisson<'a, F>(p: &'a Field<F>) -> impl Fn(f64, f64, f64) -> f64 + 'a
+where
+    F: Fn(f64, f64, f64) -> f64,
+{
+    return move |t, x, y| p.laplacian(t, x, y);
+}
+
+// The Burgers sources plus the pressure gradient
+pub fn navier_stokes<'a, F, G, H>(
+    u: &'a Field<F>,
+    v: &'a Field<G>,
+    p: &'a Field<H>,
+    rho: f64,
+    nu: f64,
+) -> (
+    impl Fn(f64, f64, f64) -> f64 + 'a,
+    impl Fn(f64, f64, f64) -> f64 + 'a,
+)
+where
+    F: Fn(f64, f64, f64) -> f64,
+    G: Fn(f64, f64, f64) -> f64,
+    H: Fn(f64, f64, f64) -> f64,
+{
+    let (bu, bv) = burgers(u, v, nu);
+    let su = move |t, x, y| bu(t, x, y) + p.dx(t, x, y) / rho;
+    let sv = move |t, x, y| bv(t, x, y) + p.dy(t, x, y) / rho;
+    return (su, sv);
+}
+
+// x9 on [0, 2]^2 with n x n nodes, up to time t
+pub fn x9<F>(u: &Field<F>, nu: f64, t: f64, n: usize, dt: f64) -> Norms
+where
+    F: Fn(f64, f64, f64) -> f64,
+{
+    let dx = 2. / (n - 1) as f64;
+    let nt = (t / dt).round() as usize;
+    let s = diffusion(u, nu);
+
+    let mut a = u.sample(0., (n, n), dx, dx);
+    // x9 takes nt + 1 steps, so it cannot take none
+    if nt > 0 {
+        x9::solve_forced(&mut a, nt - 1, dx, dx, dt, nu, |t| {
+            sample(&s, t, (n, n), dx, dx)
+        });
+    }
+    let exact = u.sample(nt as f64 * dt, (n, n), dx, dx);
+    return norms(&(&a - &exact));
+}
+
+// xa on [0, 2]^2 with n x n nodes, up to time t
+pub fn xa<F, G>(u: &Field<F>, v: &Field<G>, nu: f64, t: f64, n: usize, dt: f64) -> Norms
+where
+    F: Fn(f64, f64, f64) -> f64,
+    G: Fn(f64, f64, f64) -> f64,
+{
+    let dx = 2. / (n - 1) as f64;
+    let nt = (t / dt).round() as usize;
+    let (su, sv) = burgers(u, v, nu);
+
+    let mut a = u.sample(0., (n, n), dx, dx);
+    let mut b = v.sample(0., (n, n), dx, dx);
+    // xa takes nt + 1 steps, so it cannot take none
+    if nt > 0 {
+        xa::solve_forced(
+            &mut a,
+            &mut b,
+            nt - 1,
+            dx,
+            dx,
+            dt,
+            nu,
+            Upwinding::Backward,
+            |t| {
+                (
+                    sample(&su, t, (n, n), dx, dx),
+                    sample(&sv, t, (n, n), dx, dx),
+                )
+            },
+        );
+    }
+    let t = nt as f64 * dt;
+    return velocity_norms(
+        &a,
+        &b,
+        &u.sample(t, (n, n), dx, dx),
+        &v.sample(t, (n, n), dx, dx),
+    );
+}
+
+// xd on [0, 2] x [0, 1] with n x n nodes, iterated nt times
+pub fn xd<F>(p: &Field<F>, nt: usize, n: usize) -> (f64, Norms)
+where
+    F: Fn(f64, f64, f64) -> f64,
+{
+    let (dx, dy) = (2. / (n - 1) as f64, 1. / (n - 1) as f64);
+    let b = sample(poisson(p), 0., (n, n), dx, dy);
+
+    let mut a = Array::zeros((n, n));
+    xd::solve(&mut a, b, nt, dx, dy);
+    return (dx, norms(&(&a - &p.sample(0., (n, n), dx, dy))));
+}
+
+// xe on [0, 2]^2 with n x n nodes and a resting lid, up to time t
+pub fn xe<F, G, H>(
+    u: &Field<F>,
+    v: &Field<G>,
+    p: &Field<H>,
+    rho: f64,
+    nu: f64,
+    t: f64,
+    n: usize,
+    dt: f64,
+    options: &xe::Options,
+) -> Norms
+where
+    F: Fn(f64, f64, f64) -> f64,
+    G: Fn(f64, f64, f64) -> f64,
+    H: Fn(f64, f64, f64) -> f64,
+{
+    let dx = 2. / (n - 1) as f64;
+    let nt = (t / dt).round() as usize;
+    let (su, sv) = navier_stokes(u, v, p, rho, nu);
+    let options = xe::Options {
+        lid: 0.,
+        ..*options
+    };
+
+    let mut a = u.sample(0., (n, n), dx, dx);
+    let mut b = v.sample(0., (n, n), dx, dx);
+    let mut c = p.sample(0., (n, n), dx, dx);
+    xe::solve_forced(
+        &mut a,
+        &mut b,
+        &mut c,
+        nt,
+        dx,
+        dx,
+        dt,
+        rho,
+        nu,
+        &options,
+        |t| {
+            (
+                sample(&su, t, (n, n), dx, dx),
+                sample(&sv, t, (n, n), dx, dx),
+            )
+        },
+    );
+    let t = nt as f64 * dt;
+    return velocity_norms(
+        &a,
+        &b,
+        &u.sample(t, (n, n), dx, dx),
+        &v.sample(t, (n, n), dx, dx),
+    );
+}
+
+// xf marched to a steady state on n x n nodes, periodic over [0, 2) in x, with no body
+// force; the fields must not depend on t. None when the march does not settle.
+pub fn xf<F, G, H>(
+    u: &Field<F>,
+    v: &Field<G>,
+    p: &Field<H>,
+    rho: f64,
+    nu: f64,
+    eps: f64,
+    n: usize,
+    dt: f64,
+    pressure: PressureSolver,
+) -> Option<Norms>
+where
+    F: Fn(f64, f64, f64) -> f64,
+    G: Fn(f64, f64, f64) -> f64,
+    H: Fn(f64, f64, f64) -> f64,
+{
+    let (dx, dy) = (2. / n as f64, 2. / (n - 1) as f64);
+    let (su, sv) = navier_stokes(u, v, p, rho, nu);
+    let s = sample(&su, 0., (n, n), dx, dy);
+    let t = sample(&sv, 0., (n, n), dx, dy);
+    let options = xf::Options {
+        pressure,
+        ..Default::default()
+    };
+
+    let mut a = u.sample(0., (n, n), dx, dy);
+    let mut b = v.sample(0., (n, n), dx, dy);
+    let mut c = p.sample(0., (n, n), dx, dy);
+    xf::solve_forced(
+        &mut a,
+        &mut b,
+        &mut c,
+        dx,
+        dy,
+        dt,
+        rho,
+        nu,
+        0.,
+        eps,
+        1_000_000,
+        &options,
+        |_| (s.clone(), t.clone()),
+    )?;
+    return Some(velocity_norms(
+        &a,
+        &b,
+        &u.sample(0., (n, n), dx, dy),
+        &v.sample(0., (n, n), dx, dy),
+    ));
+}
+
+fn velocity_norms(u: &Array2<f64>, v: &Array2<f64>, ue: &Array2<f64>, ve: &Array2<f64>) -> Norms {
+    let e = ndarray::concatenate(Axis(0), &[(u - ue).view(), (v - ve).view()]).unwrap();
+    return norms(&e);
+}
@@ -0,0 +1,207 @@
+// Time-series monitors for the lessons: probes interpolated at physical points, integrated
+// quantities and per-step residuals, written as CSV rows every few steps. A lesson hands
+// its fields to `record` after every step, or its step to `march`; 1D fields are a single
+// row with dy = 1.
+use ndarray::prelude::*;
+use std::io::{self, Write};
+
+use crate::post;
+
+#[derive(Clone, Debug)]
+pub struct Probe {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    // A row is written at every multiple of `every` steps, starting with the initial state
+    pub every: usize,
+    // Every field is sampled at each probe
+    pub probes: Vec<Probe>,
+    // Sections x = const across which the flux of u is integrated
+    pub sections: Vec<f64>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            every: 1,
+            probes: Vec::new(),
+            sections: Vec::new(),
+        };
+    }
+}
+
+pub struct Monitor<W: Write> {
+    pub options: Options,
+    writer: W,
+    header: bool,
+    previous: Option<(f64, Vec<Array2<f64>>)>,
+}
+
+impl<W: Write> Monitor<W> {
+    pub fn new(writer: W, options: Options) -> Self {
+        return Monitor {
+            options,
+            writer,
+            header: false,
+            previous: None,
+        };
+    }
+
+    pub fn into_inner(self) -> W {
+        return self.writer;
+    }
+
+    // Samples the named fields at step n and time t. The fields named u and v are the
+    // velocity: the kinetic energy covers those present, and the largest divergence needs
+    // both. The residual of a field is the largest change per unit time since the last
+    // call, which is empty on the first one. `periodic` is the wrap-around in x of xf.
+    pub fn record(
+        &mut self,
+        n: usize,
+        t: f64,
+        fields: &[(&str, ArrayView2<f64>)],
+        dx: f64,
+        dy: f64,
+        periodic: bool,
+    ) -> io::Result<()> {
+        let find = |name: &str| fields.iter().find(|(f, _)| *f == name).map(|(_, a)| a);
+        let (u, v) = (find("u"), find("v"));
+        let residuals = match &self.previous {
+            Some((tn, previous)) => fields
+                .iter()
+                .zip(previous)
+                .map(|((_, f), before)| {
+                    let change = (f - before).fold(0., |a: f64, x| a.max(x.abs()));
+                    format!("{}", change / (t - tn))
+                })
+                .collect(),
+            None => vec![String::new(); fields.len()],
+        };
+        self.previous = Some((t, fields.iter().map(|(_, f)| f.to_owned()).collect()));
+        if !n.is_multiple_of(self.options.every.max(1)) {
+            return Ok(());
+        }
+
+        if !self.header {
+            let mut columns = vec![String::from("step"), String::from("t")];
+            for probe in &self.options.probes {
+                for (name, _) in fields {
+                    columns.push(format!("{}.{}", probe.name, name));
+                }
+            }
+            if u.is_some() {
+                for x in &self.options.sections {
+                    columns.push(format!("flux(x={})", x));
+                }
+            }
+            columns.push(String::from("kinetic_energy"));
+            if u.is_some() && v.is_some() {
+                columns.push(String::from("max_divergence"));
+            }
+            for (name, _) in fields {
+                columns.push(format!("residual.{}", name));
+            }
+            writeln!(self.writer, "{}", columns.join(","))?;
+            self.header = true;
+        }
+
+        let mut row = vec![format!("{}", n), format!("{}", t)];
+        for probe in &self.options.probes {
+            for (_, f) in fields {
+                row.push(format!(
+                    "{}",
+                    interpolate(f, dx, dy, probe.x, probe.y, periodic)
+                ));
+            }
+        }
+        if let Some(u) = u {
+            for &x in &self.options.sections {
+                row.push(format!("{}", flux(u, dx, dy, x, periodic)));
+            }
+        }
+        let mut energy = Array::zeros(fields[0].1.raw_dim());
+        for velocity in [u, v].into_iter().flatten() {
+            energy += &(velocity * velocity / 2.);
+        }
+        row.push(format!("{}", post::integral(&energy, dx, dy, periodic)));
+        if let (Some(u), Some(v)) = (u, v) {
+            let d = post::divergence(&u.to_owned(), &v.to_owned(), dx, dy, periodic);
+            row.push(format!("{}", d.fold(0., |a: f64, x| a.max(x.abs()))));
+        }
+        row.extend(residuals);
+        writeln!(self.writer, "{}", row.join(","))?;
+        return Ok(());
+    }
+
+    // Records the fields that `fields` takes from `state` before the first step and after
+    // every call of `step`, which advances the state by dt and returns whether to go on, for
+    // at most max_steps calls. Returns the number of steps taken.
+    pub fn march<T, S, F>(
+        &mut self,
+        state: &mut T,
+        max_steps: usize,
+        dt: f64,
+        dx: f64,
+        dy: f64,
+        periodic: bool,
+        mut step: S,
+        fields: F,
+    ) -> io::Result<usize>
+    where
+        T: ?Sized,
+        S: FnMut(&mut T) -> bool,
+        F: Fn(&T) -> Vec<(&str, ArrayView2<'_, f64>)>,
+    {
+        self.record(0, 0., &fields(state), dx, dy, periodic)?;
+        for n in 1..=max_steps {
+            let more = step(state);
+            self.record(n, n as f64 * dt, &fields(state), dx, dy, periodic)?;
+            if !more {
+                return Ok(n);
+            }
+        }
+        return Ok(max_steps);
+    }
+}
+
+// Bilinear interpolation of the nodal field f at (x, y), clamped to the grid unless x
+// wraps around
+pub fn interpolate(f: &ArrayView2<f64>, dx: f64, dy: f64, x: f64, y: f64, periodic: bool) -> f64 {
+    let (ny, nx) = f.dim();
+    let (i, a) = locate(x / dx, nx, periodic);
+    let (j, b) = locate(y / dy, ny, false);
+    let east = if periodic {
+        (i + 1) % nx
+    } else {
+        (i + 1).min(nx - 1)
+    };
+    let north = (j + 1).min(ny - 1);
+    return (1. - a) * (1. - b) * f[[j, i]]
+        + a * (1. - b) * f[[j, east]]
+        + (1. - a) * b * f[[north, i]]
+        + a * b * f[[north, east]];
+}
+
+// Node below the fractional index s and the weight of the node after it
+fn locate(s: f64, n: usize, periodic: bool) -> (usize, f64) {
+    if periodic {
+        let s = s.rem_euclid(n as f64);
+        return ((s.floor() as usize).min(n - 1), s - s.floor());
+    }
+    let s = s.clamp(0., (n - 1) as f64);
+    let k = (s.floor() as usize).min(n.saturating_sub(2));
+    return (k, s - k as f64);
+}
+
+// Volume flux of u across x = const, by the trapezoidal rule along the section
+fn flux(u: &ArrayView2<f64>, dx: f64, dy: f64, x: f64, periodic: bool) -> f64 {
+    let ny = u.nrows();
+    let profile = Array::from_shape_fn((ny, 1), |(j, _)| {
+        interpolate(u, dx, dy, x, j as f64 * dy, periodic)
+    });
+    return post::integral(&profile, 1., dy, true);
+}
@@ -0,0 +1,23 @@
+use ndarray::prelude::*;
+use ndarray::Data;
+
+// Grid-independent norms: mean absolute, root mean square and maximum value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Norms {
+    pub l1: f64,
+    pub l2: f64,
+    pub linf: f64,
+}
+
+pub fn norms<S, D>(e: &ArrayBase<S, D>) -> Norms
+where
+    S: Data<Elem = f64>,
+    D: Dimension,
+{
+    let n = e.len() as f64;
+    return Norms {
+        l1: e.iter().map(|x| x.abs()).sum::<f64>() / n,
+        l2: (e.iter().map(|x| x.powi(2)).sum::<f64>() / n).sqrt(),
+        linf: e.iter().fold(0., |a: f64, x| a.max(x.abs())),
+    };
+}
@@ -0,0 +1,214 @@
+// Direct solvers for the discrete Poisson equation on the lesson grids.
+//
+// The five-point Laplacian on a uniform grid is diagonalised by the DFT (periodic),
+// the DST-I (homogeneous Dirichlet on the boundary nodes) and the DCT-I (homogeneous
+// Neumann through a mirrored ghost node), with quarter-wave cosines for a Neumann side
+// facing a Dirichlet one, so the equation can be solved exactly in
+// O(N log N) by transforming, dividing by the eigenvalues and transforming back. Other
+// symmetric operators fall back to conjugate gradients.
+use ndarray::prelude::*;
+use rustfft::{num_complex::Complex, FftPlanner};
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary {
+    Periodic,
+    Dirichlet,
+    Neumann,
+    // Neumann on the first line and Dirichlet on the last, like the lid of the cavity
+    NeumannDirichlet,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PressureSolver {
+    Jacobi(usize),
+    Spectral,
+}
+
+pub fn dirichlet(p: &mut Array2<f64>, b: &Array2<f64>, dx: f64, dy: f64) {
+    spectral(p, b, dx, dy, Boundary::Dirichlet, Boundary::Dirichlet);
+}
+
+pub fn periodic(p: &mut Array2<f64>, b: &Array2<f64>, dx: f64, dy: f64) {
+    spectral(p, b, dx, dy, Boundary::Periodic, Boundary::Periodic);
+}
+
+pub fn channel(p: &mut Array2<f64>, b: &Array2<f64>, dx: f64, dy: f64) {
+    spectral(p, b, dx, dy, Boundary::Periodic, Boundary::Neumann);
+}
+
+// Solves lap(p) = b with `x` applied at the first/last column and `y` at the
+// first/last row. Singular problems (no Dirichlet side) return the zero-mean solution.
+pub fn spectral(p: &mut Array2<f64>, b: &Array2<f64>, dx: f64, dy: f64, x: Boundary, y: Boundary) {
+    let (ny, nx) = p.dim();
+    let (xs, ys) = (unknowns(x, nx), unknowns(y, ny));
+    let mut planner = FftPlanner::new();
+
+    let mut a = b
+        .slice(s![ys.0..ys.1, xs.0..xs.1])
+        .mapv(|x| Complex::new(x, 0.));
+    transform(&mut planner, &mut a, Axis(1), x, false);
+    transform(&mut planner, &mut a, Axis(0), y, false);
+
+    let lx = eigenvalues(x, a.shape()[1], dx);
+    let ly = eigenvalues(y, a.shape()[0], dy);
+    for ((j, i), a) in a.indexed_iter_mut() {
+        let l = lx[i] + ly[j];
+        *a = if l == 0. {
+            Complex::new(0., 0.)
+        } else {
+            *a / l
+        };
+    }
+
+    transform(&mut planner, &mut a, Axis(0), y, true);
+    transform(&mut planner, &mut a, Axis(1), x, true);
+    p.slice_mut(s![ys.0..ys.1, xs.0..xs.1])
+        .assign(&a.mapv(|x| x.re));
+
+    if x == Boundary::Dirichlet {
+        p.column_mut(0).fill(0.);
+        p.column_mut(nx - 1).fill(0.);
+    }
+    if y == Boundary::Dirichlet {
+        p.row_mut(0).fill(0.);
+        p.row_mut(ny - 1).fill(0.);
+    }
+    if x == Boundary::NeumannDirichlet {
+        p.column_mut(nx - 1).fill(0.);
+    }
+    if y == Boundary::NeumannDirichlet {
+        p.row_mut(ny - 1).fill(0.);
+    }
+}
+
+// Conjugate gradients for a(x) = b with a symmetric positive semi-definite operator `a`,
+// starting from zero and stopping at a residual of `tolerance` relative to b. Singular
+// systems converge as long as b is orthogonal to the null space of `a`.
+pub fn conjugate_gradient<A>(a: A, b: &Array2<f64>, tolerance: f64) -> Array2<f64>
+where
+    A: Fn(&Array2<f64>) -> Array2<f64>,
+{
+    let dot = |x: &Array2<f64>, y: &Array2<f64>| (x * y).sum();
+
+    let mut x = Array::zeros(b.raw_dim());
+    let mut r = b.clone();
+    let mut q = r.clone();
+    let mut rr = dot(&r, &r);
+    let stop = tolerance.powi(2) * rr;
+    for _ in 0..b.len() {
+        if rr <= stop || rr == 0. {
+            break;
+        }
+        let aq = a(&q);
+        let alpha = rr / dot(&q, &aq);
+        x += &(alpha * &q);
+        r -= &(alpha * &aq);
+        let next = dot(&r, &r);
+        q = &r + &(next / rr * &q);
+        rr = next;
+    }
+    return x;
+}
+
+fn unknowns(boundary: Boundary, n: usize) -> (usize, usize) {
+    return match boundary {
+        Boundary::Dirichlet => (1, n - 1),
+        Boundary::Periodic | Boundary::Neumann => (0, n),
+        Boundary::NeumannDirichlet => (0, n - 1),
+    };
+}
+
+fn eigenvalues(boundary: Boundary, n: usize, h: f64) -> Array1<f64> {
+    let theta = |k: usize| match boundary {
+        Boundary::Periodic => 2. * PI * k as f64 / n as f64,
+        Boundary::Dirichlet => PI * (k + 1) as f64 / (n + 1) as f64,
+        Boundary::Neumann => PI * k as f64 / (n - 1) as f64,
+        Boundary::NeumannDirichlet => PI * (k as f64 + 0.5) / n as f64,
+    };
+    return Array::from_shape_fn(n, |k| 2. * (theta(k).cos() - 1.) / h.powi(2));
+}
+
+fn transform(
+    planner: &mut FftPlanner<f64>,
+    a: &mut Array2<Complex<f64>>,
+    axis: Axis,
+    boundary: Boundary,
+    inverse: bool,
+) {
+    let n = a.len_of(axis);
+    let (m, norm) = match boundary {
+        Boundary::Periodic => (n, n as f64),
+        Boundary::Dirichlet => (2 * (n + 1), (n + 1) as f64 / 2.),
+        Boundary::Neumann => (2 * (n - 1), (2 * (n - 1)) as f64),
+        // Only the odd wavenumbers of the quarter-wave extension are present
+        Boundary::NeumannDirichlet => (4 * n, (2 * n) as f64),
+    };
+    let scale = if inverse { 1. / norm } else { 1. };
+    let synthesis = inverse && boundary == Boundary::NeumannDirichlet;
+    let fft = if inverse && (boundary == Boundary::Periodic || synthesis) {
+        planner.plan_fft_inverse(m)
+    } else {
+        planner.plan_fft_forward(m)
+    };
+
+    let zero = Complex::new(0., 0.);
+    let mut buffer = vec![zero; m];
+    for mut lane in a.lanes_mut(axis) {
+        buffer.fill(zero);
+        match boundary {
+            Boundary::Periodic => {
+                for (ith, x) in lane.iter().enumerate() {
+                    buffer[ith] = *x;
+                }
+            }
+            // Odd extension: [0, x, 0, -rev(x)]
+            Boundary::Dirichlet => {
+                for (ith, x) in lane.iter().enumerate() {
+                    buffer[ith + 1] = *x;
+                    buffer[m - 1 - ith] = -*x;
+                }
+            }
+            // Even extension: [x, rev(x[1..-1])]
+            Boundary::Neumann => {
+                for (ith, x) in lane.iter().enumerate() {
+                    buffer[ith] = *x;
+                    if ith > 0 && ith < n - 1 {
+                        buffer[m - ith] = *x;
+                    }
+                }
+            }
+            // Cosine coefficients: a_k / 2 at the wavenumbers +-(2k + 1)
+            Boundary::NeumannDirichlet if inverse => {
+                for (ith, x) in lane.iter().enumerate() {
+                    buffer[2 * ith + 1] = *x / 2.;
+                    buffer[m - 2 * ith - 1] = *x / 2.;
+                }
+            }
+            // Even about the first node and odd about the Dirichlet one:
+            // [x, 0, -rev(x), -x, 0, rev(x)]
+            Boundary::NeumannDirichlet => {
+                for (ith, x) in lane.iter().enumerate() {
+                    buffer[ith] = *x;
+                    buffer[2 * n - ith] = -*x;
+                    buffer[2 * n + ith] = -*x;
+                    if ith > 0 {
+                        buffer[m - ith] = *x;
+                    }
+                }
+            }
+        }
+
+        fft.process(&mut buffer);
+
+        for (ith, x) in lane.iter_mut().enumerate() {
+            *x = scale
+                * match boundary {
+                    Boundary::Periodic | Boundary::Neumann => buffer[ith],
+                    Boundary::Dirichlet => buffer[ith + 1] * Complex::new(0., 0.5),
+                    Boundary::NeumannDirichlet if inverse => buffer[ith],
+                    Boundary::NeumannDirichlet => buffer[2 * ith + 1],
+                };
+        }
+    }
+}
@@ -0,0 +1,135 @@
+// Derived quantities of a velocity field on the node grid of the lessons. Derivatives are
+// central in the interior and second-order one-sided on the walls, or wrap around in x
+// when the field is periodic as in xf, so every quantity is defined at every node.
+// Integrals use the trapezoidal rule, which becomes the plain sum along a periodic x.
+use ndarray::prelude::*;
+use ndarray::Zip;
+
+#[derive(Clone, Debug)]
+pub struct Post {
+    pub vorticity: Array2<f64>,
+    pub divergence: Array2<f64>,
+    pub magnitude: Array2<f64>,
+    pub q: Array2<f64>,
+    pub streamfunction: Array2<f64>,
+    // Per unit density
+    pub kinetic_energy: f64,
+    pub enstrophy: f64,
+}
+
+pub fn post(u: &Array2<f64>, v: &Array2<f64>, dx: f64, dy: f64, periodic: bool) -> Post {
+    let vorticity = vorticity(u, v, dx, dy, periodic);
+    return Post {
+        divergence: divergence(u, v, dx, dy, periodic),
+        magnitude: magnitude(u, v),
+        q: q_criterion(u, v, dx, dy, periodic),
+        streamfunction: streamfunction(u, v, dx, dy),
+        kinetic_energy: kinetic_energy(u, v, dx, dy, periodic),
+        enstrophy: enstrophy(&vorticity, dx, dy, periodic),
+        vorticity,
+    };
+}
+
+// omega = v_x - u_y
+pub fn vorticity(
+    u: &Array2<f64>,
+    v: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    periodic: bool,
+) -> Array2<f64> {
+    return derivative(v, dx, Axis(1), periodic) - derivative(u, dy, Axis(0), false);
+}
+
+pub fn divergence(
+    u: &Array2<f64>,
+    v: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    periodic: bool,
+) -> Array2<f64> {
+    return derivative(u, dx, Axis(1), periodic) + derivative(v, dy, Axis(0), false);
+}
+
+pub fn magnitude(u: &Array2<f64>, v: &Array2<f64>) -> Array2<f64> {
+    return Zip::from(u).and(v).map_collect(|u, v| u.hypot(*v));
+}
+
+// Q = (|Omega|^2 - |S|^2) / 2 of the velocity gradient; positive where rotation dominates
+// strain, which marks vortex cores
+pub fn q_criterion(
+    u: &Array2<f64>,
+    v: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    periodic: bool,
+) -> Array2<f64> {
+    let ux = derivative(u, dx, Axis(1), periodic);
+    let uy = derivative(u, dy, Axis(0), false);
+    let vx = derivative(v, dx, Axis(1), periodic);
+    let vy = derivative(v, dy, Axis(0), false);
+    return -(&ux * &ux + &vy * &vy) / 2. - &uy * &vx;
+}
+
+// psi with u = psi_y and v = -psi_x, integrated from psi = 0 at the origin along the
+// bottom row and then up each column. For a field that is not divergence-free the result
+// depends on that path; vorticity::streamfunction solves lap(psi) = -omega instead.
+pub fn streamfunction(u: &Array2<f64>, v: &Array2<f64>, dx: f64, dy: f64) -> Array2<f64> {
+    let (ny, nx) = u.dim();
+    let mut psi = Array::zeros((ny, nx));
+    for i in 1..nx {
+        psi[[0, i]] = psi[[0, i - 1]] - dx * (v[[0, i - 1]] + v[[0, i]]) / 2.;
+    }
+    for j in 1..ny {
+        for i in 0..nx {
+            psi[[j, i]] = psi[[j - 1, i]] + dy * (u[[j - 1, i]] + u[[j, i]]) / 2.;
+        }
+    }
+    return psi;
+}
+
+// Integral of (u^2 + v^2) / 2 over the domain
+pub fn kinetic_energy(u: &Array2<f64>, v: &Array2<f64>, dx: f64, dy: f64, periodic: bool) -> f64 {
+    return integral(&((u * u + v * v) / 2.), dx, dy, periodic);
+}
+
+// Integral of omega^2 / 2 over the domain
+pub fn enstrophy(omega: &Array2<f64>, dx: f64, dy: f64, periodic: bool) -> f64 {
+    return integral(&(omega * omega / 2.), dx, dy, periodic);
+}
+
+// Trapezoidal rule over the nodes; a single row is a 1D field
+pub fn integral(f: &Array2<f64>, dx: f64, dy: f64, periodic: bool) -> f64 {
+    let (ny, nx) = f.dim();
+    let weight = |k: usize, n: usize, wrap: bool| -> f64 {
+        if !wrap && n > 1 && (k == 0 || k == n - 1) {
+            return 0.5;
+        }
+        return 1.;
+    };
+    let mut sum = 0.;
+    for ((j, i), value) in f.indexed_iter() {
+        sum += weight(j, ny, false) * weight(i, nx, periodic) * value;
+    }
+    return sum * dx * dy;
+}
+
+// First derivative along `axis`, whose nodes are `d` apart
+fn derivative(f: &Array2<f64>, d: f64, axis: Axis, periodic: bool) -> Array2<f64> {
+    let n = f.len_of(axis);
+    let mut df = Array::zeros(f.raw_dim());
+    for (lane, mut out) in f.lanes(axis).into_iter().zip(df.lanes_mut(axis)) {
+        for k in 0..n {
+            out[k] = if periodic {
+                (lane[(k + 1) % n] - lane[(k + n - 1) % n]) / (2. * d)
+            } else if k == 0 {
+                (-3. * lane[0] + 4. * lane[1] - lane[2]) / (2. * d)
+            } else if k == n - 1 {
+                (3. * lane[k] - 4. * lane[k - 1] + lane[k - 2]) / (2. * d)
+            } else {
+                (lane[k + 1] - lane[k - 1]) / (2. * d)
+            };
+        }
+    }
+    return df;
+}
@@ -0,0 +1,220 @@
+// Fractional-step (projection) solvers for the cavity and the channel. Each step predicts
+// a velocity u* from convection, viscosity and any body force, then subtracts the gradient
+// of a potential phi chosen so that the central-difference divergence D of the lessons
+// vanishes: u = u* - G phi with D G phi = D u*. G is the adjoint of -D on the interior
+// nodes, so D G is a wide-stencil Laplacian cut off at the walls, which is solved by
+// conjugate gradients. Unlike xe and xf, the divergence after a step is zero up to the
+// tolerance of that solve.
+use ndarray::prelude::*;
+
+use crate::implicit::{helmholtz, Viscous};
+use crate::macros::{assign, fill, i};
+use crate::norm::{norms, Norms};
+use crate::poisson::conjugate_gradient;
+use crate::upwind::{convection, Upwinding};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    // Chorin (1968): the predictor ignores the pressure, which is rho phi / dt
+    Chorin,
+    // Incremental (Kim & Moin 1985): the predictor keeps the old pressure gradient and
+    // rho phi / dt corrects it
+    Incremental,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Case {
+    // Lid velocity along the last row, no-slip walls elsewhere
+    Cavity(f64),
+    // Body force along x, periodic in x between no-slip walls
+    Channel(f64),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub projection: Projection,
+    pub convection: Upwinding,
+    pub viscous: Viscous,
+    // Relative residual of the conjugate-gradient solve
+    pub tolerance: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            projection: Projection::Incremental,
+            convection: Upwinding::Backward,
+            viscous: Viscous::Explicit,
+            tolerance: 1e-10,
+        };
+    }
+}
+
+// One step; returns the norms of the divergence at the interior nodes afterwards
+pub fn step(
+    u: &mut Array2<f64>,
+    v: &mut Array2<f64>,
+    p: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    rho: f64,
+    nu: f64,
+    case: Case,
+    options: &Options,
+) -> Norms {
+    let periodic = matches!(case, Case::Channel(_));
+    let (un, vn) = (u.clone(), v.clone());
+    let explicit = match options.viscous {
+        Viscous::Explicit => 1.,
+        Viscous::Implicit(method) => 1. - method.theta(),
+    };
+    let cu = convection(&un, &un, &vn, dx, dy, options.convection, periodic);
+    let cv = convection(&vn, &un, &vn, dx, dy, options.convection, periodic);
+    let (px, py) = match options.projection {
+        Projection::Chorin => (Array::zeros(p.raw_dim()), Array::zeros(p.raw_dim())),
+        Projection::Incremental => gradient(p, dx, dy, periodic),
+    };
+    let force = match case {
+        Case::Cavity(_) => 0.,
+        Case::Channel(f) => f,
+    };
+    let mask = mask(u.dim(), periodic);
+
+    // Predictor
+    *u = &un
+        + &(dt
+            * (-cu + explicit * nu * laplacian(&un, dx, dy, periodic) - px / rho + force * &mask));
+    *v = &vn + &(dt * (-cv + explicit * nu * laplacian(&vn, dx, dy, periodic) - py / rho));
+    set_velocity_boundary_conditions(u, v, case);
+    if let Viscous::Implicit(method) = options.viscous {
+        let r = method.theta() * nu * dt;
+        *u = helmholtz(u, r / dx.powi(2), r / dy.powi(2), periodic);
+        *v = helmholtz(v, r / dx.powi(2), r / dy.powi(2), periodic);
+    }
+
+    // Projection
+    let phi = potential(
+        &divergence(u, v, dx, dy, periodic),
+        dx,
+        dy,
+        periodic,
+        options.tolerance,
+    );
+    let (gx, gy) = gradient(&phi, dx, dy, periodic);
+    *u -= &gx;
+    *v -= &gy;
+    match options.projection {
+        Projection::Chorin => p.assign(&(rho / dt * &phi)),
+        Projection::Incremental => *p += &(rho / dt * &phi),
+    }
+    set_pressure_boundary_conditions(p, periodic);
+
+    let d = divergence(u, v, dx, dy, periodic);
+    return if periodic {
+        norms(&d.slice(s![1..-1, ..]))
+    } else {
+        norms(&d.slice(s![1..-1, 1..-1]))
+    };
+}
+
+// Central-difference divergence at the interior nodes, zero elsewhere
+pub fn divergence(
+    u: &Array2<f64>,
+    v: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    periodic: bool,
+) -> Array2<f64> {
+    let (ny, nx) = u.dim();
+    let mask = mask((ny, nx), periodic);
+    return Array::from_shape_fn((ny, nx), |(j, i)| {
+        if mask[[j, i]] == 0. {
+            return 0.;
+        }
+        let (east, west) = ((i + 1) % nx, (i + nx - 1) % nx);
+        return (u[[j, east]] - u[[j, west]]) / (2. * dx)
+            + (v[[j + 1, i]] - v[[j - 1, i]]) / (2. * dy);
+    });
+}
+
+// 1 at the nodes that are solved for, 0 at the nodes held by a boundary condition
+fn mask((ny, nx): (usize, usize), periodic: bool) -> Array2<f64> {
+    return Array::from_shape_fn((ny, nx), |(j, i)| {
+        let wall = j == 0 || j == ny - 1 || (!periodic && (i == 0 || i == nx - 1));
+        if wall {
+            0.
+        } else {
+            1.
+        }
+    });
+}
+
+// Central-difference gradient at the interior nodes, zero elsewhere
+fn gradient(phi: &Array2<f64>, dx: f64, dy: f64, periodic: bool) -> (Array2<f64>, Array2<f64>) {
+    let (ny, nx) = phi.dim();
+    let mask = mask((ny, nx), periodic);
+    let gx = Array::from_shape_fn((ny, nx), |(j, i)| {
+        mask[[j, i]] * (phi[[j, (i + 1) % nx]] - phi[[j, (i + nx - 1) % nx]]) / (2. * dx)
+    });
+    let gy = Array::from_shape_fn((ny, nx), |(j, i)| {
+        if mask[[j, i]] == 0. {
+            return 0.;
+        }
+        return (phi[[j + 1, i]] - phi[[j - 1, i]]) / (2. * dy);
+    });
+    return (gx, gy);
+}
+
+// Five-point Laplacian at the interior nodes, zero elsewhere
+fn laplacian(phi: &Array2<f64>, dx: f64, dy: f64, periodic: bool) -> Array2<f64> {
+    let (ny, nx) = phi.dim();
+    let mask = mask((ny, nx), periodic);
+    return Array::from_shape_fn((ny, nx), |(j, i)| {
+        if mask[[j, i]] == 0. {
+            return 0.;
+        }
+        let (east, west) = ((i + 1) % nx, (i + nx - 1) % nx);
+        return (phi[[j, east]] - 2. * phi[[j, i]] + phi[[j, west]]) / dx.powi(2)
+            + (phi[[j + 1, i]] - 2. * phi[[j, i]] + phi[[j - 1, i]]) / dy.powi(2);
+    });
+}
+
+// -D G phi = -d is symmetric and positive semi-definite; phi vanishes at the nodes held by
+// a boundary condition
+fn potential(d: &Array2<f64>, dx: f64, dy: f64, periodic: bool, tolerance: f64) -> Array2<f64> {
+    let a = |x: &Array2<f64>| {
+        let (gx, gy) = gradient(x, dx, dy, periodic);
+        return -divergence(&gx, &gy, dx, dy, periodic);
+    };
+    return conjugate_gradient(a, &-d, tolerance);
+}
+
+fn set_velocity_boundary_conditions(u: &mut Array2<f64>, v: &mut Array2<f64>, case: Case) {
+    fill!(u[0, ..] = 0.);
+    fill!(v[0, ..] = 0.);
+    fill!(v[-1, ..] = 0.);
+    match case {
+        Case::Cavity(lid) => {
+            fill!(u[-1, ..] = lid);
+            fill!(u[.., 0] = 0.);
+            fill!(u[.., -1] = 0.);
+            fill!(v[.., 0] = 0.);
+            fill!(v[.., -1] = 0.);
+        }
+        Case::Channel(_) => fill!(u[-1, ..] = 0.),
+    }
+}
+
+fn set_pressure_boundary_conditions(p: &mut Array2<f64>, periodic: bool) {
+    let pn = i!(p[1, ..]).to_owned();
+    assign!(p[0, ..] = pn); // dp/dy = 0 @ y = 0
+    let pn = i!(p[-2, ..]).to_owned();
+    assign!(p[-1, ..] = pn); // dp/dy = 0 @ y = 2
+    if !periodic {
+        let pn = i!(p[.., 1]).to_owned();
+        assign!(p[.., 0] = pn); // dp/dx = 0 @ x = 0
+        let pn = i!(p[.., -2]).to_owned();
+        assign!(p[.., -1] = pn); // dp/dx = 0 @ x = 2
+    }
+}
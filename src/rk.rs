@@ -0,0 +1,84 @@
+// Explicit Runge-Kutta integrators for the method of lines: a lesson supplies the spatial
+// right-hand side L(t, u) of u_t = L(t, u) and an integrator advances it. Nodes held by a
+// boundary condition stay fixed as long as L vanishes there.
+use ndarray::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Integrator {
+    Euler,
+    // Second order, also SSP
+    Heun,
+    // Third-order strong-stability-preserving scheme of Shu & Osher
+    SspRk3,
+    // Classical fourth order
+    Rk4,
+    // Williamson's three-stage, third-order scheme with two registers
+    LowStorage,
+}
+
+impl Integrator {
+    pub fn order(self) -> usize {
+        return match self {
+            Integrator::Euler => 1,
+            Integrator::Heun => 2,
+            Integrator::SspRk3 => 3,
+            Integrator::Rk4 => 4,
+            Integrator::LowStorage => 3,
+        };
+    }
+}
+
+// nt steps of dt from t = 0
+pub fn march<D, L>(u: &mut Array<f64, D>, nt: usize, dt: f64, integrator: Integrator, l: L)
+where
+    D: Dimension,
+    L: Fn(f64, &Array<f64, D>) -> Array<f64, D>,
+{
+    for n in 0..nt {
+        step(u, n as f64 * dt, dt, integrator, &l);
+    }
+}
+
+// One step from t to t + dt
+pub fn step<D, L>(u: &mut Array<f64, D>, t: f64, dt: f64, integrator: Integrator, l: L)
+where
+    D: Dimension,
+    L: Fn(f64, &Array<f64, D>) -> Array<f64, D>,
+{
+    match integrator {
+        Integrator::Euler => {
+            let k1 = l(t, u);
+            *u += &(dt * k1);
+        }
+        Integrator::Heun => {
+            let u1 = &*u + &(dt * l(t, u));
+            let u2 = &u1 + &(dt * l(t + dt, &u1));
+            *u = (&*u + &u2) / 2.;
+        }
+        Integrator::SspRk3 => {
+            let u1 = &*u + &(dt * l(t, u));
+            let u2 = 0.75 * &*u + 0.25 * (&u1 + &(dt * l(t + dt, &u1)));
+            let u3 = &u2 + &(dt * l(t + dt / 2., &u2));
+            *u = &*u / 3. + 2. / 3. * u3;
+        }
+        Integrator::Rk4 => {
+            let k1 = l(t, u);
+            let k2 = l(t + dt / 2., &(&*u + &(dt / 2. * &k1)));
+            let k3 = l(t + dt / 2., &(&*u + &(dt / 2. * &k2)));
+            let k4 = l(t + dt, &(&*u + &(dt * &k3)));
+            *u += &(dt / 6. * (k1 + 2. * k2 + 2. * k3 + k4));
+        }
+        Integrator::LowStorage => {
+            let (a, b, c) = (
+                [0., -5. / 9., -153. / 128.],
+                [1. / 3., 15. / 16., 8. / 15.],
+                [0., 1. / 3., 3. / 4.],
+            );
+            let mut du = Array::zeros(u.raw_dim());
+            for k in 0..3 {
+                du = a[k] * du + dt * l(t + c[k] * dt, u);
+                *u += &(b[k] * &du);
+            }
+        }
+    }
+}
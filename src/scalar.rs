@@ -0,0 +1,101 @@
+// Passive scalar (temperature, concentration) carried by a velocity field on the node grid
+// of the lessons: phi_t + u phi_x + v phi_y = kappa lap(phi) + s. Convection is that of
+// the 2D lessons in upwind, diffusion the five-point operator of x9, and the scalar does
+// not act back on the flow.
+use ndarray::prelude::*;
+
+use crate::lesson::x9;
+use crate::macros::{add, assign, i};
+use crate::upwind::{convection, Upwinding};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Condition {
+    // Value on the wall
+    Dirichlet(f64),
+    // Outward normal derivative, by a one-sided difference
+    Neumann(f64),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub convection: Upwinding,
+    // Walls along y = 0 and y = H, then x = 0 and x = L unless the field is periodic in x
+    pub bottom: Condition,
+    pub top: Condition,
+    pub left: Condition,
+    pub right: Condition,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            convection: Upwinding::DonorCell,
+            bottom: Condition::Neumann(0.),
+            top: Condition::Neumann(0.),
+            left: Condition::Neumann(0.),
+            right: Condition::Neumann(0.),
+        };
+    }
+}
+
+// One explicit step with the velocity (u, v) and the source s per unit time
+pub fn step(
+    phi: &mut Array2<f64>,
+    u: &Array2<f64>,
+    v: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    kappa: f64,
+    periodic: bool,
+    options: &Options,
+    source: Option<&Array2<f64>>,
+) {
+    let c = convection(phi, u, v, dx, dy, options.convection, periodic);
+    let d = diffusion(phi, dx, dy, kappa, periodic);
+    add!(phi[1..-1, ..] += dt * (i!(d[1..-1, ..]) - i!(c[1..-1, ..])));
+    if let Some(s) = source {
+        add!(phi[1..-1, ..] += dt * i!(s[1..-1, ..]));
+    }
+    set_boundary_conditions(phi, dx, dy, periodic, options);
+}
+
+// kappa lap(phi) on the interior rows, on every column when periodic in x
+fn diffusion(phi: &Array2<f64>, dx: f64, dy: f64, kappa: f64, periodic: bool) -> Array2<f64> {
+    if !periodic {
+        return x9::rhs(phi, dx, dy, kappa);
+    }
+    let (ny, nx) = phi.dim();
+    let mut wrapped = Array::zeros((ny, nx + 2));
+    assign!(wrapped[.., 1..-1] = phi);
+    assign!(wrapped[.., 0] = i!(phi[.., -1]));
+    assign!(wrapped[.., -1] = i!(phi[.., 0]));
+    return x9::rhs(&wrapped, dx, dy, kappa)
+        .slice(s![.., 1..-1])
+        .to_owned();
+}
+
+fn set_boundary_conditions(
+    phi: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    periodic: bool,
+    options: &Options,
+) {
+    let (ny, nx) = phi.dim();
+    if !periodic {
+        set_wall(phi, Axis(1), 0, 1, dx, options.left);
+        set_wall(phi, Axis(1), nx - 1, nx - 2, dx, options.right);
+    }
+    set_wall(phi, Axis(0), 0, 1, dy, options.bottom);
+    set_wall(phi, Axis(0), ny - 1, ny - 2, dy, options.top);
+}
+
+// Sets the line `wall` along `axis` from the condition and its neighbour `inner`, h away
+fn set_wall(phi: &mut Array2<f64>, axis: Axis, wall: usize, inner: usize, h: f64, c: Condition) {
+    let values = match c {
+        Condition::Dirichlet(value) => Array::from_elem(phi.len_of(Axis(1 - axis.index())), value),
+        Condition::Neumann(gradient) => &phi.index_axis(axis, inner) + gradient * h,
+    };
+    phi.index_axis_mut(axis, wall).assign(&values);
+}
@@ -0,0 +1,323 @@
+// Steady finite-volume solvers on the MAC grid by pressure-velocity coupling: SIMPLE of
+// Patankar & Spalding (1972), SIMPLEC of Van Doormaal & Raithby (1984) and PISO of Issa
+// (1986). Each outer iteration linearises the momentum equations about the last
+// velocities with the hybrid differencing scheme, under-relaxes and solves them, then
+// corrects the pressure and the face velocities so that every cell conserves mass. There
+// is no time step: the iterations converge to the steady state directly.
+use ndarray::prelude::*;
+
+use crate::mac::{self, Mac};
+use crate::norm::{norms, Norms};
+use crate::poisson::conjugate_gradient;
+use crate::projection::Case;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+    Simple,
+    // Consistent SIMPLE, which keeps the neighbour sum in the velocity correction and so
+    // tolerates alpha_p = 1
+    Simplec,
+    // SIMPLE followed by further pressure correctors, as many as given in total
+    Piso(usize),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub algorithm: Algorithm,
+    // Under-relaxation of the velocities and of the pressure, the latter in the first PISO
+    // corrector only
+    pub alpha_u: f64,
+    pub alpha_p: f64,
+    // Gauss-Seidel sweeps over the momentum equations per outer iteration
+    pub sweeps: usize,
+    // Relative residual of the conjugate-gradient pressure-correction solve
+    pub tolerance: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            algorithm: Algorithm::Simple,
+            alpha_u: 0.7,
+            alpha_p: 0.3,
+            sweeps: 4,
+            tolerance: 1e-8,
+        };
+    }
+}
+
+// a_P x_P = a_E x_E + a_W x_W + a_N x_N + a_S x_S + b at every face solved for, with the
+// under-relaxation but without the pressure; zero at the other faces
+struct Momentum {
+    e: Array2<f64>,
+    w: Array2<f64>,
+    n: Array2<f64>,
+    s: Array2<f64>,
+    p: Array2<f64>,
+    b: Array2<f64>,
+}
+
+pub fn cavity(
+    mac: &mut Mac,
+    rho: f64,
+    nu: f64,
+    lid: f64,
+    iterations: usize,
+    eps: f64,
+    options: &Options,
+) -> Vec<Norms> {
+    return solve(mac, rho, nu, Case::Cavity(lid), iterations, eps, options);
+}
+
+pub fn channel(
+    mac: &mut Mac,
+    rho: f64,
+    nu: f64,
+    f: f64,
+    iterations: usize,
+    eps: f64,
+    options: &Options,
+) -> Vec<Norms> {
+    return solve(mac, rho, nu, Case::Channel(f), iterations, eps, options);
+}
+
+// Outer iterations until the largest mass imbalance per unit area of a cell falls below
+// `eps`; returns the norms of the imbalance left by the momentum solve of each iteration
+pub fn solve(
+    mac: &mut Mac,
+    rho: f64,
+    nu: f64,
+    case: Case,
+    iterations: usize,
+    eps: f64,
+    options: &Options,
+) -> Vec<Norms> {
+    let mut residuals = Vec::new();
+    for _ in 0..iterations {
+        let imbalance = iterate(mac, rho, nu, case, options);
+        residuals.push(imbalance);
+        // A diverged iteration leaves NaN in l2, which the maximum in linf skips
+        if imbalance.linf < eps || !imbalance.l2.is_finite() {
+            break;
+        }
+    }
+    return residuals;
+}
+
+fn iterate(mac: &mut Mac, rho: f64, nu: f64, case: Case, options: &Options) -> Norms {
+    let periodic = matches!(case, Case::Channel(_));
+    let (dx, dy) = (mac.dx, mac.dy);
+    mac::set_velocity_boundary_conditions(&mut mac.u, &mut mac.v, case);
+    let (mu, mv) = momentum(mac, rho, nu, case, options.alpha_u);
+
+    // Predictor
+    for _ in 0..options.sweeps {
+        sweep(mac, &mu, &mv, periodic, true);
+        mac::set_velocity_boundary_conditions(&mut mac.u, &mut mac.v, case);
+    }
+    let imbalance = norms(&mac.divergence());
+
+    let d = |m: &Momentum, area: f64| -> Array2<f64> {
+        let a = match options.algorithm {
+            Algorithm::Simplec => &m.p - &m.e - &m.w - &m.n - &m.s,
+            Algorithm::Simple | Algorithm::Piso(_) => m.p.clone(),
+        };
+        return a.mapv(|a| if a > 0. { area / a } else { 0. });
+    };
+    let (mut du, dv) = (d(&mu, dy), d(&mv, dx));
+    if periodic {
+        let first = du.column(0).to_owned();
+        let last = du.ncols() - 1;
+        du.column_mut(last).assign(&first);
+    }
+
+    let correctors = match options.algorithm {
+        Algorithm::Piso(n) => n.max(1),
+        Algorithm::Simple | Algorithm::Simplec => 1,
+    };
+    for corrector in 0..correctors {
+        if corrector > 0 {
+            // Velocities of the momentum equations with the corrected neighbours and pressure
+            sweep(mac, &mu, &mv, periodic, false);
+            mac::set_velocity_boundary_conditions(&mut mac.u, &mut mac.v, case);
+        }
+        // Only the first corrector is under-relaxed: the later ones remove what is left of
+        // the imbalance of the relaxed pressure
+        let alpha_p = if corrector == 0 { options.alpha_p } else { 1. };
+        correct(mac, &du, &dv, rho, periodic, alpha_p, options.tolerance);
+        mac::set_velocity_boundary_conditions(&mut mac.u, &mut mac.v, case);
+    }
+    return imbalance;
+}
+
+// Hybrid coefficient of a neighbour with the mass flux f towards the face and conductance d
+fn hybrid(f: f64, d: f64) -> f64 {
+    return f.max(d + f / 2.).max(0.);
+}
+
+fn momentum(mac: &Mac, rho: f64, nu: f64, case: Case, alpha: f64) -> (Momentum, Momentum) {
+    let (u, v) = (&mac.u, &mac.v);
+    let (dx, dy) = (mac.dx, mac.dy);
+    let (ny, nx) = mac.cells();
+    let (lid, force, periodic) = match case {
+        Case::Cavity(lid) => (lid, 0., false),
+        Case::Channel(f) => (0., f, true),
+    };
+    let (gx, gy) = (rho * nu * dy / dx, rho * nu * dx / dy);
+    let zeros = |m: &Array2<f64>| Momentum {
+        e: Array::zeros(m.raw_dim()),
+        w: Array::zeros(m.raw_dim()),
+        n: Array::zeros(m.raw_dim()),
+        s: Array::zeros(m.raw_dim()),
+        p: Array::zeros(m.raw_dim()),
+        b: Array::zeros(m.raw_dim()),
+    };
+    // Relaxes a_P x_P = sum(a x) + b towards the last value of x
+    let set = |m: &mut Momentum, (r, c): (usize, usize), a: [f64; 4], ap: f64, b: f64, x: f64| {
+        (m.e[[r, c]], m.w[[r, c]], m.n[[r, c]], m.s[[r, c]]) = (a[0], a[1], a[2], a[3]);
+        m.p[[r, c]] = ap / alpha;
+        m.b[[r, c]] = b + (1. - alpha) / alpha * ap * x;
+    };
+
+    let mut mu = zeros(u);
+    let wrap = |c: isize| c.rem_euclid(nx as isize) as usize;
+    let columns = if periodic { 0..nx } else { 1..nx };
+    for r in 1..=ny {
+        for c in columns.clone() {
+            let (w, e) = (wrap(c as isize - 1), wrap(c as isize + 1));
+            let fe = rho * (u[[r, c]] + u[[r, e]]) / 2. * dy;
+            let fw = rho * (u[[r, w]] + u[[r, c]]) / 2. * dy;
+            let f_n = rho * (v[[r, c]] + v[[r, c + 1]]) / 2. * dx;
+            let fs = rho * (v[[r - 1, c]] + v[[r - 1, c + 1]]) / 2. * dx;
+            let mut a = [
+                hybrid(-fe, gx),
+                hybrid(fw, gx),
+                hybrid(-f_n, gy),
+                hybrid(fs, gy),
+            ];
+            let mut ap: f64 = a.iter().sum();
+            let mut b = force * dx * dy;
+            // The ghost value 2 U - u_P beyond a wall moving at U folds into a_P and b
+            if r == 1 {
+                ap += a[3];
+                a[3] = 0.;
+            }
+            if r == ny {
+                ap += a[2];
+                b += 2. * a[2] * lid;
+                a[2] = 0.;
+            }
+            set(&mut mu, (r, c), a, ap, b, u[[r, c]]);
+        }
+    }
+
+    let mut mv = zeros(v);
+    for r in 1..ny {
+        for c in 1..=nx {
+            let fe = rho * (u[[r, c]] + u[[r + 1, c]]) / 2. * dy;
+            let fw = rho * (u[[r, c - 1]] + u[[r + 1, c - 1]]) / 2. * dy;
+            let f_n = rho * (v[[r, c]] + v[[r + 1, c]]) / 2. * dx;
+            let fs = rho * (v[[r - 1, c]] + v[[r, c]]) / 2. * dx;
+            let mut a = [
+                hybrid(-fe, gx),
+                hybrid(fw, gx),
+                hybrid(-f_n, gy),
+                hybrid(fs, gy),
+            ];
+            let mut ap: f64 = a.iter().sum();
+            if !periodic && c == 1 {
+                ap += a[1];
+                a[1] = 0.;
+            }
+            if !periodic && c == nx {
+                ap += a[0];
+                a[0] = 0.;
+            }
+            set(&mut mv, (r, c), a, ap, 0., v[[r, c]]);
+        }
+    }
+    return (mu, mv);
+}
+
+// Solves the momentum equations for every face with the current pressure, in place
+// (Gauss-Seidel) or from the velocities at the start of the sweep (Jacobi)
+fn sweep(mac: &mut Mac, mu: &Momentum, mv: &Momentum, periodic: bool, in_place: bool) {
+    let (dx, dy) = (mac.dx, mac.dy);
+    let (ny, nx) = mac.cells();
+    let p = &mac.p;
+    let wrap = |c: isize| c.rem_euclid(nx as isize) as usize;
+    let columns = if periodic { 0..nx } else { 1..nx };
+
+    let un = mac.u.clone();
+    let u = &mut mac.u;
+    for r in 1..=ny {
+        for c in columns.clone() {
+            let (w, e) = (wrap(c as isize - 1), wrap(c as isize + 1));
+            let x = if in_place { &*u } else { &un };
+            let sum = mu.e[[r, c]] * x[[r, e]]
+                + mu.w[[r, c]] * x[[r, w]]
+                + mu.n[[r, c]] * x[[r + 1, c]]
+                + mu.s[[r, c]] * x[[r - 1, c]];
+            u[[r, c]] = (sum + mu.b[[r, c]] + dy * (p[[r, c]] - p[[r, c + 1]])) / mu.p[[r, c]];
+        }
+    }
+
+    let vn = mac.v.clone();
+    let v = &mut mac.v;
+    for r in 1..ny {
+        for c in 1..=nx {
+            let x = if in_place { &*v } else { &vn };
+            let sum = mv.e[[r, c]] * x[[r, c + 1]]
+                + mv.w[[r, c]] * x[[r, c - 1]]
+                + mv.n[[r, c]] * x[[r + 1, c]]
+                + mv.s[[r, c]] * x[[r - 1, c]];
+            v[[r, c]] = (sum + mv.b[[r, c]] + dx * (p[[r, c]] - p[[r + 1, c]])) / mv.p[[r, c]];
+        }
+    }
+}
+
+// Solves for the pressure correction p' that removes the mass imbalance of every cell
+// when each face velocity moves by d (p'_P - p'_neighbour), and applies it, to the
+// pressure under-relaxed by alpha_p
+fn correct(
+    mac: &mut Mac,
+    du: &Array2<f64>,
+    dv: &Array2<f64>,
+    rho: f64,
+    periodic: bool,
+    alpha_p: f64,
+    tolerance: f64,
+) {
+    let (dx, dy) = (mac.dx, mac.dy);
+    let (ny, nx) = mac.cells();
+    let a = |q: &Array2<f64>| {
+        let q = mac::pad(q, periodic);
+        return Array::from_shape_fn((ny, nx), |(j, i)| {
+            let (r, c) = (j + 1, i + 1);
+            rho * dy
+                * (du[[r, c]] * (q[[r, c]] - q[[r, c + 1]])
+                    + du[[r, c - 1]] * (q[[r, c]] - q[[r, c - 1]]))
+                + rho
+                    * dx
+                    * (dv[[r, c]] * (q[[r, c]] - q[[r + 1, c]])
+                        + dv[[r - 1, c]] * (q[[r, c]] - q[[r - 1, c]]))
+        });
+    };
+    let b = -rho * dx * dy * mac.divergence();
+    let correction = conjugate_gradient(a, &b, tolerance);
+    let q = mac::pad(&correction, periodic);
+
+    for r in 1..=ny {
+        for c in 0..=nx {
+            mac.u[[r, c]] += du[[r, c]] * (q[[r, c]] - q[[r, c + 1]]);
+        }
+    }
+    for r in 1..ny {
+        for c in 1..=nx {
+            mac.v[[r, c]] += dv[[r, c]] * (q[[r, c]] - q[[r + 1, c]]);
+        }
+    }
+    let p = mac.p.slice(s![1..-1, 1..-1]).to_owned() + alpha_p * correction;
+    let level = p.mean().unwrap();
+    mac.p = mac::pad(&(p - level), periodic);
+}
@@ -0,0 +1,151 @@
+// Convective terms u phi_x + v phi_y of the 2D lessons, with the one-sided differences
+// taken on the side the flow comes from. Only interior rows are computed; columns are
+// either interior too or wrap around when the domain is periodic in x. Near a boundary
+// the wider stencils fall back to donor-cell.
+use ndarray::prelude::*;
+
+use crate::macros::{assign, i};
+use crate::tvd::Limiter;
+use crate::weno::{self, Weno};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Upwinding {
+    // Backward differences whatever the sign of the velocity, as in the lessons
+    Backward,
+    DonorCell,
+    SecondOrder,
+    Quick,
+    // Upwind face values of the limited MUSCL profiles
+    Muscl(Limiter),
+    // Upwind face values of the fifth-order WENO reconstruction
+    Weno5(Weno),
+}
+
+pub fn convection(
+    phi: &Array2<f64>,
+    u: &Array2<f64>,
+    v: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    upwinding: Upwinding,
+    periodic: bool,
+) -> Array2<f64> {
+    let (ny, nx) = phi.dim();
+    let mut c = Array::zeros((ny, nx));
+    if upwinding == Upwinding::Backward {
+        // The lessons' slices; the first column takes its west neighbour from the last
+        let last = if periodic { nx } else { nx - 1 };
+        assign!(
+            c[1..-1, 1..last] = i!(u[1..-1, 1..last])
+                * ((i!(phi[1..-1, 1..last]) - i!(phi[1..-1, ..last - 1])) / dx)
+                + i!(v[1..-1, 1..last]) * ((i!(phi[1..-1, 1..last]) - i!(phi[..-2, 1..last])) / dy)
+        );
+        if periodic {
+            assign!(
+                c[1..-1, 0] = i!(u[1..-1, 0]) * ((i!(phi[1..-1, 0]) - i!(phi[1..-1, -1])) / dx)
+                    + i!(v[1..-1, 0]) * ((i!(phi[1..-1, 0]) - i!(phi[..-2, 0])) / dy)
+            );
+        }
+        return c;
+    }
+    let columns = if periodic { 0..nx } else { 1..nx - 1 };
+    for j in 1..ny - 1 {
+        for i in columns.clone() {
+            let row = |k: isize| -> Option<f64> {
+                let k = i as isize + k;
+                if periodic {
+                    return Some(phi[[j, k.rem_euclid(nx as isize) as usize]]);
+                }
+                return (0..nx as isize).contains(&k).then(|| phi[[j, k as usize]]);
+            };
+            let column = |k: isize| -> Option<f64> {
+                let k = j as isize + k;
+                return (0..ny as isize).contains(&k).then(|| phi[[k as usize, i]]);
+            };
+            c[[j, i]] = u[[j, i]] * derivative(row, u[[j, i]], dx, upwinding)
+                + v[[j, i]] * derivative(column, v[[j, i]], dy, upwinding);
+        }
+    }
+    return c;
+}
+
+// u phi_x + v phi_y + w phi_z of the 3D lessons on [k, j, i] = (z, y, x) arrays, at the
+// interior nodes
+pub fn convection3(
+    phi: &Array3<f64>,
+    u: &Array3<f64>,
+    v: &Array3<f64>,
+    w: &Array3<f64>,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    upwinding: Upwinding,
+) -> Array3<f64> {
+    let (nz, ny, nx) = phi.dim();
+    let mut c = Array::zeros((nz, ny, nx));
+    for k in 1..nz - 1 {
+        for j in 1..ny - 1 {
+            for i in 1..nx - 1 {
+                // phi along each axis through the node, if the line has such a node
+                let line = |axis: usize, o: isize| -> Option<f64> {
+                    let mut index = [k as isize, j as isize, i as isize];
+                    index[axis] += o;
+                    let n = phi.shape()[axis] as isize;
+                    return (0..n)
+                        .contains(&index[axis])
+                        .then(|| phi[[index[0] as usize, index[1] as usize, index[2] as usize]]);
+                };
+                let (a, b, e) = (u[[k, j, i]], v[[k, j, i]], w[[k, j, i]]);
+                c[[k, j, i]] = a * derivative(|o| line(2, o), a, dx, upwinding)
+                    + b * derivative(|o| line(1, o), b, dy, upwinding)
+                    + e * derivative(|o| line(0, o), e, dz, upwinding);
+            }
+        }
+    }
+    return c;
+}
+
+// `phi(k)` is the value k nodes further along the line, if the line has such a node
+fn derivative<P: Fn(isize) -> Option<f64>>(phi: P, a: f64, h: f64, upwinding: Upwinding) -> f64 {
+    let s = if a < 0. { -1 } else { 1 };
+    let donor = || s as f64 * (phi(0).unwrap() - phi(-s).unwrap()) / h;
+    return match upwinding {
+        Upwinding::Backward => (phi(0).unwrap() - phi(-1).unwrap()) / h,
+        Upwinding::DonorCell => donor(),
+        Upwinding::SecondOrder => match phi(-2 * s) {
+            Some(far) => s as f64 * (3. * phi(0).unwrap() - 4. * phi(-s).unwrap() + far) / (2. * h),
+            None => donor(),
+        },
+        Upwinding::Quick => match (phi(-2 * s), phi(s)) {
+            (Some(far), Some(next)) => {
+                s as f64 * (3. * next + 3. * phi(0).unwrap() - 7. * phi(-s).unwrap() + far)
+                    / (8. * h)
+            }
+            _ => donor(),
+        },
+        Upwinding::Muscl(limiter) => {
+            // Face value on the upwind side of the face between the node k and k + s
+            let face = |k: isize| -> Option<f64> {
+                let (back, here, next) = (phi((k - 1) * s)?, phi(k * s)?, phi((k + 1) * s)?);
+                return Some(here + limiter.slope(here - back, next - here) / 2.);
+            };
+            match (face(0), face(-1)) {
+                (Some(out), Some(into)) => s as f64 * (out - into) / h,
+                _ => donor(),
+            }
+        }
+        Upwinding::Weno5(kind) => {
+            let face = |k: isize| -> Option<f64> {
+                let mut v = [0.; 5];
+                for (o, x) in v.iter_mut().enumerate() {
+                    *x = phi((k + o as isize - 2) * s)?;
+                }
+                return Some(weno::reconstruct(v, kind));
+            };
+            match (face(0), face(-1)) {
+                (Some(out), Some(into)) => s as f64 * (out - into) / h,
+                _ => donor(),
+            }
+        }
+    };
+}
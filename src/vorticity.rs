@@ -0,0 +1,169 @@
+// Vorticity-streamfunction formulation of the lid-driven cavity on the node grid of xe:
+// omega_t + u omega_x + v omega_y = nu lap(omega), lap(psi) = -omega, u = psi_y and
+// v = -psi_x. The walls are the streamline psi = 0, and their vorticity follows from the
+// no-slip condition by Thom's formula, so no pressure is needed.
+use ndarray::prelude::*;
+
+use crate::lesson::xd;
+use crate::macros::{add, assign, i};
+use crate::poisson::{self, PressureSolver};
+use crate::upwind::{convection, Upwinding};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    // Solver of the streamfunction equation; Jacobi iterations are those of xd, and at
+    // least one
+    pub poisson: PressureSolver,
+    pub convection: Upwinding,
+    pub lid: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            poisson: PressureSolver::Spectral,
+            convection: Upwinding::Backward,
+            lid: 1.,
+        };
+    }
+}
+
+pub fn solve(
+    omega: &mut Array2<f64>,
+    psi: &mut Array2<f64>,
+    nt: usize,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    options: &Options,
+) {
+    for _ in 0..nt {
+        step(omega, psi, dx, dy, dt, nu, options);
+    }
+}
+
+// Marches until the largest vorticity change per unit time falls below `eps`; returns the
+// number of steps, or None when `max_steps` pass first or the march blows up
+pub fn steady(
+    omega: &mut Array2<f64>,
+    psi: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    eps: f64,
+    max_steps: usize,
+    options: &Options,
+) -> Option<usize> {
+    for stepcount in 1..=max_steps {
+        let omegan = omega.clone();
+        step(omega, psi, dx, dy, dt, nu, options);
+
+        let diff = (&*omega - &omegan).fold(0., |a: f64, x| a.max(x.abs())) / dt;
+        // max skips NaN, so a blow-up shows in the sum
+        if !(diff + omega.sum()).is_finite() {
+            return None;
+        }
+        if diff <= eps {
+            return Some(stepcount);
+        }
+    }
+    return None;
+}
+
+fn step(
+    omega: &mut Array2<f64>,
+    psi: &mut Array2<f64>,
+    dx: f64,
+    dy: f64,
+    dt: f64,
+    nu: f64,
+    options: &Options,
+) {
+    set_wall_vorticity(omega, psi, dx, dy, options.lid);
+    let (u, v) = velocity(psi, dx, dy, options.lid);
+    let on = omega.clone();
+    let c = convection(&on, &u, &v, dx, dy, options.convection, false);
+    add!(
+        omega[1..-1, 1..-1] += dt
+            * (nu
+                * ((i!(on[1..-1, 2..]) - 2. * i!(on[1..-1, 1..-1]) + i!(on[1..-1, ..-2]))
+                    / dx.powi(2)
+                    + (i!(on[2.., 1..-1]) - 2. * i!(on[1..-1, 1..-1]) + i!(on[..-2, 1..-1]))
+                        / dy.powi(2))
+                - i!(c[1..-1, 1..-1]))
+    );
+    streamfunction_of(psi, omega, dx, dy, options.poisson);
+}
+
+// Solves lap(psi) = -omega with psi = 0 on the walls, from the current psi
+pub fn streamfunction_of(
+    psi: &mut Array2<f64>,
+    omega: &Array2<f64>,
+    dx: f64,
+    dy: f64,
+    solver: PressureSolver,
+) {
+    let b = -omega;
+    match solver {
+        // xd runs nt + 1 sweeps
+        PressureSolver::Jacobi(nit) => {
+            assert!(
+                nit > 0,
+                "the streamfunction needs at least one Jacobi sweep"
+            );
+            xd::solve(psi, b, nit - 1, dx, dy);
+        }
+        PressureSolver::Spectral => poisson::dirichlet(psi, &b, dx, dy),
+    }
+}
+
+// The streamfunction of a primitive-variable cavity field such as that of xe, from its
+// central-difference vorticity
+pub fn streamfunction(u: &Array2<f64>, v: &Array2<f64>, dx: f64, dy: f64) -> Array2<f64> {
+    let mut omega = Array::zeros(u.raw_dim());
+    assign!(
+        omega[1..-1, 1..-1] = (i!(v[1..-1, 2..]) - i!(v[1..-1, ..-2])) / (2. * dx)
+            - (i!(u[2.., 1..-1]) - i!(u[..-2, 1..-1])) / (2. * dy)
+    );
+    let mut psi = Array::zeros(u.raw_dim());
+    streamfunction_of(&mut psi, &omega, dx, dy, PressureSolver::Spectral);
+    return psi;
+}
+
+// u = psi_y and v = -psi_x by central differences, with the wall velocities on the edges
+pub fn velocity(psi: &Array2<f64>, dx: f64, dy: f64, lid: f64) -> (Array2<f64>, Array2<f64>) {
+    let mut u = Array::zeros(psi.raw_dim());
+    let mut v = Array::zeros(psi.raw_dim());
+    assign!(u[1..-1, 1..-1] = (i!(psi[2.., 1..-1]) - i!(psi[..-2, 1..-1])) / (2. * dy));
+    assign!(v[1..-1, 1..-1] = -(i!(psi[1..-1, 2..]) - i!(psi[1..-1, ..-2])) / (2. * dx));
+    let last = u.nrows() - 1;
+    u.slice_mut(s![last, 1..-1]).fill(lid);
+    return (u, v);
+}
+
+// Position (x, y) and value of the extremum of psi among the nodes with `inside(x, y)`:
+// the minimum for a clockwise vortex, the maximum for an anticlockwise one
+pub fn centre<F>(psi: &Array2<f64>, dx: f64, dy: f64, clockwise: bool, inside: F) -> (f64, f64, f64)
+where
+    F: Fn(f64, f64) -> bool,
+{
+    let sign = if clockwise { -1. } else { 1. };
+    let mut best = (0., 0., 0.);
+    for ((j, i), &value) in psi.indexed_iter() {
+        let (x, y) = (i as f64 * dx, j as f64 * dy);
+        if inside(x, y) && sign * value > sign * best.2 {
+            best = (x, y, value);
+        }
+    }
+    return best;
+}
+
+// Thom (1933): psi = 0 and d(psi)/dn = wall velocity give omega on the walls
+fn set_wall_vorticity(omega: &mut Array2<f64>, psi: &Array2<f64>, dx: f64, dy: f64, lid: f64) {
+    assign!(omega[0, ..] = -2. * i!(psi[1, ..]) / dy.powi(2)); // y = 0
+    assign!(omega[-1, ..] = -2. * i!(psi[-2, ..]) / dy.powi(2) - 2. * lid / dy); // y = 2
+    assign!(omega[.., 0] = -2. * i!(psi[.., 1]) / dx.powi(2)); // x = 0
+    assign!(omega[.., -1] = -2. * i!(psi[.., -2]) / dx.powi(2)); // x = 2
+}
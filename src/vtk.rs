@@ -0,0 +1,74 @@
+// Legacy VTK output of the lesson fields for ParaView or VisIt. The nodes of a 1D, 2D or
+// 3D lesson are written as STRUCTURED_POINTS from the origin with x varying fastest, which
+// is the logical order of the [i], [j, i] and [k, j, i] arrays; missing directions have a
+// single point and missing vector components are zero.
+use ndarray::prelude::*;
+use std::io::{self, Write};
+
+// Writes `scalars` and the `vectors` given by their components (u, v[, w]), all on the
+// grid of spacing (dx, dy, dz)
+pub fn write<W: Write, D: Dimension>(
+    mut writer: W,
+    title: &str,
+    (dx, dy, dz): (f64, f64, f64),
+    scalars: &[(&str, ArrayView<f64, D>)],
+    vectors: &[(&str, &[ArrayView<f64, D>])],
+) -> io::Result<()> {
+    let shapes = scalars.iter().map(|(_, f)| f.shape()).chain(
+        vectors
+            .iter()
+            .flat_map(|(_, c)| c.iter().map(|f| f.shape())),
+    );
+    let mut shape: Option<&[usize]> = None;
+    for s in shapes {
+        if shape.is_some_and(|shape| shape != s) || s.len() > 3 {
+            return Err(invalid(
+                "fields differ in shape or have more than three axes",
+            ));
+        }
+        shape = Some(s);
+    }
+    if vectors.iter().any(|(_, c)| c.is_empty() || c.len() > 3) {
+        return Err(invalid("vectors have one to three components"));
+    }
+    let shape = shape.ok_or_else(|| invalid("no fields to write"))?;
+    // (nx, ny, nz) from the axes in reverse
+    let mut dimensions = [1; 3];
+    for (d, n) in dimensions.iter_mut().zip(shape.iter().rev()) {
+        *d = *n;
+    }
+    let points: usize = dimensions.iter().product();
+
+    writeln!(writer, "# vtk DataFile Version 3.0")?;
+    writeln!(writer, "{}", title.lines().next().unwrap_or(""))?;
+    writeln!(writer, "ASCII")?;
+    writeln!(writer, "DATASET STRUCTURED_POINTS")?;
+    let [nx, ny, nz] = dimensions;
+    writeln!(writer, "DIMENSIONS {} {} {}", nx, ny, nz)?;
+    writeln!(writer, "ORIGIN 0 0 0")?;
+    writeln!(writer, "SPACING {} {} {}", dx, dy, dz)?;
+    writeln!(writer, "POINT_DATA {}", points)?;
+    for (name, f) in scalars {
+        writeln!(writer, "SCALARS {} double 1", name)?;
+        writeln!(writer, "LOOKUP_TABLE default")?;
+        for x in f.iter() {
+            writeln!(writer, "{}", x)?;
+        }
+    }
+    for (name, components) in vectors {
+        writeln!(writer, "VECTORS {} double", name)?;
+        let mut lanes: Vec<_> = components.iter().map(|c| c.iter()).collect();
+        for _ in 0..points {
+            let mut v = [0.; 3];
+            for (x, lane) in v.iter_mut().zip(lanes.iter_mut()) {
+                *x = *lane.next().unwrap();
+            }
+            writeln!(writer, "{} {} {}", v[0], v[1], v[2])?;
+        }
+    }
+    return Ok(());
+}
+
+fn invalid(message: &str) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidInput, message);
+}
@@ -0,0 +1,98 @@
+use cfd_rust::advection::{self, Scheme};
+use cfd_rust::analytic;
+use cfd_rust::convergence::{self, TimeStep};
+use cfd_rust::lesson;
+use cfd_rust::norm::Norms;
+use ndarray::prelude::*;
+
+fn square_wave(scheme: Scheme) -> (Array1<f64>, Norms) {
+    let (mut u, nt, dx, dt, c) = lesson::x3::default();
+    lesson::x3::solve_with(&mut u, nt, dx, dt, c, scheme);
+    let e = analytic::errors(&u, dx, nt as f64 * dt, |x, t| {
+        analytic::advected_hat(x, t, c)
+    });
+    return (u, e);
+}
+
+fn bounds(u: &Array1<f64>) -> (f64, f64) {
+    return (
+        u.fold(f64::INFINITY, |a, &x| a.min(x)),
+        u.fold(f64::NEG_INFINITY, |a, &x| a.max(x)),
+    );
+}
+
+#[test]
+fn diffusion() {
+    // The first-order schemes stay within the initial bounds, Lax-Friedrichs smearing more
+    let (upwind, eu) = square_wave(Scheme::Upwind);
+    let (lax, el) = square_wave(Scheme::LaxFriedrichs);
+    for u in [upwind, lax] {
+        let (min, max) = bounds(&u);
+        assert!(min >= 1. - 1e-12 && max <= 2. + 1e-12);
+    }
+    assert!(el.l1 > 1.5 * eu.l1);
+}
+
+#[test]
+fn dispersion() {
+    // The second-order schemes ring around the discontinuities
+    let (_, eu) = square_wave(Scheme::Upwind);
+    for scheme in [Scheme::LaxWendroff, Scheme::MacCormack, Scheme::BeamWarming] {
+        let (u, e) = square_wave(scheme);
+        let (min, max) = bounds(&u);
+        assert!(min < 0.9 && max > 2.1);
+        assert!(e.l1 < eu.l1);
+    }
+}
+
+#[test]
+fn maccormack() {
+    // Both reduce to the same scheme for a linear flux
+    let (a, _) = square_wave(Scheme::LaxWendroff);
+    let (b, _) = square_wave(Scheme::MacCormack);
+    assert!((&a - &b).iter().all(|x| x.abs() < 1e-12));
+}
+
+#[test]
+fn orders() {
+    let (c, t) = (1., 0.5);
+    let step = TimeStep {
+        sigma: 0.5,
+        power: 1,
+    };
+    for (scheme, order) in [
+        (Scheme::Upwind, 1.),
+        (Scheme::LaxFriedrichs, 1.),
+        (Scheme::LaxWendroff, 2.),
+        (Scheme::MacCormack, 2.),
+        (Scheme::BeamWarming, 2.),
+    ] {
+        let grids = convergence::grids(161, 2, 4);
+        let study = convergence::exact_in_time(&grids, 2., step, |n, dx, dt| {
+            let nt = (t / dt).round() as usize;
+            let mut u =
+                Array::from_shape_fn(n, |ith| analytic::gaussian(ith as f64 * dx, 0.7, 0.15));
+            advection::linear(&mut u, nt, dx, dt, c, scheme);
+            analytic::errors(&u, dx, nt as f64 * dt, |x, t| {
+                analytic::gaussian(x - c * t, 0.7, 0.15)
+            })
+        });
+        assert!((study.order(|e| e.l1) - order).abs() < 0.1);
+    }
+}
+
+#[test]
+fn burgers() {
+    // The conservative schemes move the shock at the right speed
+    for scheme in [
+        Scheme::LaxFriedrichs,
+        Scheme::LaxWendroff,
+        Scheme::MacCormack,
+        Scheme::BeamWarming,
+    ] {
+        let (mut u, nt, dx, dt) = lesson::x2::default();
+        lesson::x2::solve_with(&mut u, nt, dx, dt, scheme);
+        let e = analytic::errors(&u, dx, nt as f64 * dt, analytic::burgers_hat);
+        assert!(e.l1 < 0.1);
+    }
+}
@@ -0,0 +1,57 @@
+use cfd_rust::analytic::{self, erf};
+use cfd_rust::lesson;
+
+#[test]
+fn erf_values() {
+    // Abramowitz & Stegun, Table 7.1
+    for (x, y) in [(0.5, 0.5204998778), (1., 0.8427007929), (2., 0.9953222650)] {
+        assert!((erf(x) - y).abs() < 1e-10 && (erf(-x) + y).abs() < 1e-10);
+    }
+    assert!((1. - erf(4.) - 1.541725790e-8).abs() < 1e-15);
+}
+
+#[test]
+fn x1() {
+    let (mut u, nt, dx, dt, c) = lesson::x1::default();
+    lesson::x1::solve(&mut u, nt, dx, dt, c);
+    let e = analytic::errors(&u, dx, nt as f64 * dt, |x, t| {
+        analytic::advected_hat(x, t, c)
+    });
+    assert!(e.l1 < 0.15);
+}
+
+#[test]
+fn x2() {
+    let (mut u, nt, dx, dt) = lesson::x2::default();
+    lesson::x2::solve(&mut u, nt, dx, dt);
+    let e = analytic::errors(&u, dx, nt as f64 * dt, analytic::burgers_hat);
+    assert!(e.l1 < 0.25);
+}
+
+#[test]
+fn x3() {
+    let (mut u, nt, dx, dt, c) = lesson::x3::default();
+    lesson::x3::solve(&mut u, nt, dx, dt, c);
+    let e = analytic::errors(&u, dx, nt as f64 * dt, |x, t| {
+        analytic::advected_hat(x, t, c)
+    });
+    assert!(e.l1 < 0.1);
+}
+
+#[test]
+fn x4() {
+    let (mut u, nt, dx, dt, nu) = lesson::x4::default();
+    lesson::x4::solve(&mut u, nt, dx, dt, nu);
+    let e = analytic::errors(&u, dx, nt as f64 * dt, |x, t| {
+        analytic::diffused_hat(x, t, nu)
+    });
+    assert!(e.l1 < 0.05);
+}
+
+#[test]
+fn x5() {
+    let (mut u, nt, dx, dt, nu) = lesson::x5::default();
+    lesson::x5::solve(&mut u, nt, dx, dt, nu);
+    let e = analytic::errors(&u, dx, nt as f64 * dt, |x, t| analytic::burgers(x, t, nu));
+    assert!(e.l1 < 0.25);
+}
@@ -0,0 +1,56 @@
+use cfd_rust::boussinesq::{self, Options, DE_VAHL_DAVIS};
+use ndarray::prelude::*;
+
+#[test]
+fn de_vahl_davis() {
+    for (ra, nu, umax, vmax) in &DE_VAHL_DAVIS[..2] {
+        let (mut u, mut v, mut p, mut t, dx) = boussinesq::initial(41);
+        let options = Options::default();
+        boussinesq::steady(
+            &mut u, &mut v, &mut p, &mut t, dx, 1e-4, *ra, 0.71, 1e-3, 100_000, &options,
+        )
+        .unwrap();
+        let (hot, cold) = boussinesq::nusselt(&t, dx, dx);
+        assert!((hot / nu - 1.).abs() < 1e-2);
+        // The heat entering through the hot wall leaves through the cold one
+        assert!((hot - cold).abs() < 1e-6);
+        let (u_max, v_max) = boussinesq::maxima(&u, &v);
+        assert!((u_max / umax - 1.).abs() < 2e-2 && (v_max / vmax - 1.).abs() < 2e-2);
+
+        // The flow turns clockwise and is symmetric about the centre of the cavity
+        let flipped = |a: &Array2<f64>| a.slice(s![..;-1, ..;-1]).to_owned();
+        assert!((&t + &flipped(&t)).iter().all(|e| e.abs() < 1e-6));
+        assert!((&u + &flipped(&u)).iter().all(|e| e.abs() < 1e-6));
+        assert!(v[[20, 5]] > 0. && v[[20, 35]] < 0.);
+    }
+}
+
+#[test]
+fn conduction() {
+    // Without buoyancy heat crosses the cavity by conduction alone: T = 1/2 - x, Nu = 1
+    let (mut u, mut v, mut p, mut t, dx) = boussinesq::initial(21);
+    let options = Options::default();
+    boussinesq::steady(
+        &mut u, &mut v, &mut p, &mut t, dx, 4e-4, 0., 0.71, 1e-6, 100_000, &options,
+    )
+    .unwrap();
+    for ((_, i), value) in t.indexed_iter() {
+        assert!((value - (0.5 - i as f64 * dx)).abs() < 1e-6);
+    }
+    assert!(u.iter().chain(v.iter()).all(|x| x.abs() < 1e-12));
+    let (hot, cold) = boussinesq::nusselt(&t, dx, dx);
+    assert!((hot - 1.).abs() < 1e-6 && (cold - 1.).abs() < 1e-6);
+}
+
+#[test]
+fn steady_failures() {
+    let options = Options::default();
+    let run = |dt, max_steps| {
+        let (mut u, mut v, mut p, mut t, dx) = boussinesq::initial(21);
+        return boussinesq::steady(
+            &mut u, &mut v, &mut p, &mut t, dx, dt, 1e4, 0.71, 1e-3, max_steps, &options,
+        );
+    };
+    assert_eq!(run(1e-4, 150), None);
+    assert_eq!(run(1e-1, 10_000), None);
+}
@@ -0,0 +1,143 @@
+use cfd_rust::benchmark;
+use cfd_rust::lesson::xe::{self, Options, PressureBc};
+use cfd_rust::mac::Mac;
+use cfd_rust::poisson::PressureSolver;
+use cfd_rust::simple;
+use ndarray::prelude::*;
+
+// CFDPython Step 11 after nt = 100, sampled every 5 nodes:
+// u(y) and p(y) along x = 1, v(x) along y = 1
+const U: [f64; 9] = [
+    0.0,
+    -0.010115224400587394,
+    -0.012481771388626899,
+    -0.016688650555348617,
+    -0.023224612749598337,
+    -0.03191233042974348,
+    -0.04009129204716893,
+    0.035200764956972906,
+    1.0,
+];
+const V: [f64; 9] = [
+    0.0,
+    0.021971948282896135,
+    0.01586023349273414,
+    0.007829609802806321,
+    2.557528085045288e-06,
+    -0.007818691975026916,
+    -0.015852775909968364,
+    -0.021986533013594788,
+    0.0,
+];
+const P: [f64; 9] = [
+    0.007458877504951148,
+    0.0074442720004294785,
+    0.007390519203741751,
+    0.007272610867034944,
+    0.007034634419163343,
+    0.006613297260211593,
+    0.006124201299567784,
+    0.006564790514515535,
+    0.0,
+];
+
+fn run(nt: usize, options: &Options) -> (Array2<f64>, Array2<f64>, Array2<f64>) {
+    let (mut u, mut v, mut p, _, _, dx, dy, dt, rho, nu) = xe::default();
+    xe::solve_with(&mut u, &mut v, &mut p, nt, dx, dy, dt, rho, nu, options);
+    return (u, v, p);
+}
+
+#[test]
+fn cfdpython() {
+    let (u, v, p) = run(100, &Options::default());
+    let close = |a: ArrayView1<f64>, b: &[f64]| {
+        return a
+            .iter()
+            .step_by(5)
+            .zip(b)
+            .all(|(a, b)| (a - b).abs() < 1e-10);
+    };
+    assert!(close(u.column(20), &U));
+    assert!(close(v.row(20), &V));
+    assert!(close(p.column(20), &P));
+}
+
+#[test]
+fn pressure_level() {
+    let reference = Options {
+        pressure_bc: PressureBc::Reference(0, 0),
+        ..Default::default()
+    };
+    let zero_mean = Options {
+        pressure_bc: PressureBc::ZeroMean,
+        ..Default::default()
+    };
+    let (u, v, p) = run(100, &reference);
+    let (un, vn, pn) = run(100, &zero_mean);
+    assert_eq!(p[[0, 0]], 0.);
+    assert!(pn.mean().unwrap().abs() < 1e-12);
+
+    // Both fix the level of the same pure-Neumann problem
+    let offset = (&p - &pn).mean().unwrap();
+    assert!((&p - &pn - offset).iter().all(|x| x.abs() < 1e-10));
+    assert!((&u - &un)
+        .iter()
+        .chain((&v - &vn).iter())
+        .all(|x| x.abs() < 1e-10));
+}
+
+#[test]
+#[should_panic(expected = "outside the 41 x 41 grid")]
+fn pressure_reference() {
+    let options = Options {
+        pressure_bc: PressureBc::Reference(41, 0),
+        ..Default::default()
+    };
+    run(1, &options);
+}
+
+#[test]
+fn ghia() {
+    let options = Options {
+        pressure: PressureSolver::Spectral,
+        pressure_bc: PressureBc::ZeroMean,
+        ..Default::default()
+    };
+    let (u, v, _, _, _) = benchmark::xe_cavity(100, &options).unwrap();
+    let (eu, ev) = benchmark::cavity(&u, &v, 100).unwrap();
+    assert!(eu.linf < 0.02 && ev.linf < 0.02);
+}
+
+// The lesson is too coarse at 41 nodes to resolve Re = 400, which the hybrid differencing
+// of SIMPLE reaches on as many cells
+#[test]
+fn ghia_400() {
+    let n = 40;
+    let h = 2. / n as f64;
+    let mut m = Mac::new(n, n, h, h);
+    let nu = benchmark::cavity_nu(400);
+    let residuals = simple::cavity(&mut m, 1., nu, 1., 2000, 1e-6, &Default::default());
+    assert!(residuals.last().unwrap().linf < 1e-6);
+    let (u, v, _) = m.nodes();
+    let (eu, ev) = benchmark::cavity(&u, &v, 400).unwrap();
+    assert!(eu.l2 < 0.02 && ev.l2 < 0.04);
+    // The profiles are those of Re = 400 and not of Re = 100
+    let (fu, fv) = benchmark::cavity(&u, &v, 100).unwrap();
+    assert!(eu.l2 < fu.l2 / 2. && ev.l2 < fv.l2 / 2.);
+}
+
+#[test]
+fn steady_failures() {
+    let (u0, v0, p0, _, _, dx, dy, _, rho, nu) = xe::default();
+    let run = |dt, max_steps| {
+        let (mut u, mut v, mut p) = (u0.clone(), v0.clone(), p0.clone());
+        let options = Options::default();
+        return xe::steady(
+            &mut u, &mut v, &mut p, dx, dy, dt, rho, nu, 1e-3, max_steps, &options,
+        );
+    };
+    // Too few steps to settle
+    assert_eq!(run(0.001, 10), None);
+    // Far past the explicit viscous limit
+    assert_eq!(run(1., 10_000), None);
+}
@@ -0,0 +1,55 @@
+use cfd_rust::channel;
+use cfd_rust::lesson::xf;
+
+// Wall shear rho f H / 2 and Cf Re = 12 with Re = ub H / nu
+fn check(u: &ndarray::Array2<f64>, dx: f64, dy: f64, rho: f64, nu: f64, f: f64, tolerance: f64) {
+    let h = 2.;
+    let (bottom, top) = channel::wall_shear(u, dy, rho, nu);
+    for tau in bottom.iter().chain(top.iter()) {
+        assert!((tau / (rho * f * h / 2.) - 1.).abs() < tolerance);
+    }
+    let ub = channel::bulk_velocity(u, dy);
+    assert!((ub / (f * h * h / (12. * nu)) - 1.).abs() < tolerance);
+    let rates = channel::flow_rates(u, dy);
+    assert!(rates.iter().all(|q| (q / (ub * h) - 1.).abs() < 1e-12));
+    let re = ub * h / nu;
+    assert!((channel::skin_friction(u, dy, rho, nu) * re / 12. - 1.).abs() < tolerance);
+    let force = rho * f * h * u.ncols() as f64 * dx;
+    assert!((channel::drag(u, dx, dy, rho, nu) / force - 1.).abs() < tolerance);
+}
+
+#[test]
+fn poiseuille() {
+    // The one-sided differences are exact for the parabola, the trapezoidal rule is not
+    let (u, _, _, _, dx, dy, _, rho, nu, f, _) = xf::default();
+    let u = channel::poiseuille(&u, dy, nu, f);
+    assert!((u[[20, 0]] - f / (2. * nu)).abs() < 1e-12);
+    let (bottom, top) = channel::wall_shear(&u, dy, rho, nu);
+    assert!(bottom
+        .iter()
+        .chain(top.iter())
+        .all(|tau| (tau - rho * f).abs() < 1e-12));
+    check(&u, dx, dy, rho, nu, f, 1e-3);
+}
+
+#[test]
+fn developed() {
+    let (mut u, mut v, mut p, nit, dx, dy, dt, rho, nu, f, _) = xf::default();
+    xf::solve(&mut u, &mut v, &mut p, nit, dx, dy, dt, rho, nu, f, 1e-6);
+    let exact = channel::poiseuille(&u, dy, nu, f);
+    assert!((&u - &exact).iter().all(|e| e.abs() < 5e-3));
+    assert!(v.iter().all(|v| v.abs() < 1e-12));
+    check(&u, dx, dy, rho, nu, f, 2e-3);
+}
+
+#[test]
+fn developing() {
+    // The lesson's tolerance stops while the walls still hold back less than the force
+    let (mut u, mut v, mut p, nit, dx, dy, dt, rho, nu, f, eps) = xf::default();
+    xf::solve(&mut u, &mut v, &mut p, nit, dx, dy, dt, rho, nu, f, eps);
+    let force = rho * f * 2. * u.ncols() as f64 * dx;
+    let drag = channel::drag(&u, dx, dy, rho, nu);
+    assert!(drag > 0. && drag < 0.9 * force);
+    let exact = channel::poiseuille(&u, dy, nu, f);
+    assert!(channel::flow_rate(&u, dy) < channel::flow_rate(&exact, dy));
+}
@@ -0,0 +1,158 @@
+use cfd_rust::analytic;
+use cfd_rust::convergence::{self, richardson, TimeStep};
+use cfd_rust::lesson;
+use cfd_rust::norm::norms;
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+// Smooth bump on [0, 2] advected with sigma = c dt / dx = 0.5, before it steepens into a shock
+const CONVECTION: TimeStep = TimeStep {
+    sigma: 0.5,
+    power: 1,
+};
+
+fn convection(n: usize, dx: f64, dt: f64, nonlinear: bool) -> Array1<f64> {
+    let nt = (0.1 / dt).round() as usize;
+    let mut u = Array::from_shape_fn(n, |ith| analytic::gaussian(ith as f64 * dx, 0.7, 0.15));
+    if nonlinear {
+        lesson::x2::solve(&mut u, nt, dx, dt);
+    } else {
+        lesson::x1::solve(&mut u, nt, dx, dt, 1.);
+    }
+    return u;
+}
+
+#[test]
+fn x1() {
+    let grids = convergence::grids(161, 2, 4);
+    let study = convergence::exact_in_time(&grids, 2., CONVECTION, |n, dx, dt| {
+        let u = convection(n, dx, dt, false);
+        analytic::errors(&u, dx, 0.1, |x, t| analytic::gaussian(x - t, 0.7, 0.15))
+    });
+    assert!((study.order(|e| e.l1) - 1.).abs() < 0.1);
+}
+
+#[test]
+fn x2() {
+    let grids = [81, 161, 321, 641, 5121];
+    let study = convergence::finest_in_time(&grids, 2., CONVECTION, |n, dx, dt| {
+        convection(n, dx, dt, true)
+    });
+    assert!((study.order(|e| e.l1) - 1.).abs() < 0.1);
+}
+
+#[test]
+#[should_panic(expected = "not nested")]
+fn x2_nesting() {
+    convergence::finest_in_time(&[81, 100, 161], 2., CONVECTION, |n, dx, dt| {
+        convection(n, dx, dt, true)
+    });
+}
+
+#[test]
+fn x3() {
+    let (c, t) = (1., 0.5);
+    let step = TimeStep {
+        sigma: 0.8,
+        power: 1,
+    };
+    let study =
+        convergence::exact_in_time(&convergence::grids(161, 2, 4), 2., step, |n, dx, dt| {
+            let nt = (t / dt).round() as usize;
+            let mut u =
+                Array::from_shape_fn(n, |ith| analytic::gaussian(ith as f64 * dx, 0.7, 0.15));
+            lesson::x3::solve(&mut u, nt, dx, dt, c);
+            analytic::errors(&u, dx, nt as f64 * dt, |x, t| {
+                analytic::gaussian(x - c * t, 0.7, 0.15)
+            })
+        });
+    assert!((study.order(|e| e.l1) - 1.).abs() < 0.1);
+}
+
+// A bump on [0, 2] diffused up to t = 0.05 with nu = 0.3 and sigma = nu dt / dx^2 = 0.2
+const NU: f64 = 0.3;
+const DIFFUSION: TimeStep = TimeStep {
+    sigma: 0.2 / NU,
+    power: 2,
+};
+
+fn diffusion(n: usize, dx: f64, dt: f64) -> (f64, Array1<f64>) {
+    let nt = (0.05 / dt).round() as usize;
+    let mut u = Array::from_shape_fn(n, |ith| analytic::gaussian(ith as f64 * dx, 1., 0.1));
+    lesson::x4::solve(&mut u, nt, dx, dt, NU);
+    return (nt as f64 * dt, u);
+}
+
+#[test]
+fn x4() {
+    let grids = convergence::grids(21, 2, 4);
+    let study = convergence::exact_in_time(&grids, 2., DIFFUSION, |n, dx, dt| {
+        let (t, u) = diffusion(n, dx, dt);
+        analytic::errors(&u, dx, t, |x, t| {
+            analytic::diffused_gaussian(x, t, NU, 1., 0.1)
+        })
+    });
+    assert!((study.order(|e| e.l2) - 2.).abs() < 0.1);
+
+    // Richardson extrapolation of u(1, t) from three grids
+    let centre: Vec<f64> = convergence::grids(41, 2, 3)
+        .iter()
+        .map(|&n| {
+            let dx = 2. / (n - 1) as f64;
+            diffusion(n, dx, DIFFUSION.dt(dx)).1[(n - 1) / 2]
+        })
+        .collect();
+    let (p, value) = richardson(centre[0], centre[1], centre[2], 2.);
+    let exact = analytic::diffused_gaussian(1., 0.05, 0.3, 1., 0.1);
+    assert!((p - 2.).abs() < 0.1);
+    assert!((value - exact).abs() < (centre[2] - exact).abs() / 10.);
+}
+
+#[test]
+fn x5() {
+    let (nu, t) = (0.07, 0.44);
+    let step = TimeStep {
+        sigma: 0.2 / nu,
+        power: 2,
+    };
+    let grids = convergence::grids(201, 2, 5);
+    let study = convergence::exact_in_time(&grids, 2. * PI, step, |n, dx, dt| {
+        let nt = (t / dt).round() as usize;
+        let mut u = Array::from_shape_fn(n, |ith| analytic::burgers(ith as f64 * dx, 0., nu));
+        lesson::x5::solve(&mut u, nt, dx, dt, nu);
+        analytic::errors(&u, dx, nt as f64 * dt, |x, t| analytic::burgers(x, t, nu))
+    });
+    assert!((study.order(|e| e.l1) - 1.).abs() < 0.1);
+}
+
+#[test]
+fn x9() {
+    let (nu, t) = (0.05, 0.2);
+    let step = TimeStep {
+        sigma: 0.25 / nu,
+        power: 2,
+    };
+    let study = convergence::exact_in_time(&convergence::grids(21, 2, 4), 2., step, |n, dx, dt| {
+        let nt = (t / dt).round() as usize;
+        let mut u = Array::from_shape_fn((n, n), |(j, i)| {
+            analytic::diffused_gaussian2(i as f64 * dx, j as f64 * dx, 0., nu, 1., 1., 0.2)
+        });
+        // x9 takes nt + 1 steps, so it cannot take none
+        if nt > 0 {
+            lesson::x9::solve(&mut u, nt - 1, dx, dx, dt, nu);
+        }
+        let exact = Array::from_shape_fn((n, n), |(j, i)| {
+            analytic::diffused_gaussian2(
+                i as f64 * dx,
+                j as f64 * dx,
+                nt as f64 * dt,
+                nu,
+                1.,
+                1.,
+                0.2,
+            )
+        });
+        norms(&(&u - &exact))
+    });
+    assert!((study.order(|e| e.l2) - 2.).abs() < 0.1);
+}
@@ -0,0 +1,213 @@
+use cfd_rust::analytic;
+use cfd_rust::convergence::{self, Study, TimeStep};
+use cfd_rust::implicit::{cyclic, thomas, Method, Viscous};
+use cfd_rust::lesson::{x4, x9, xe, xf};
+use cfd_rust::mms;
+use cfd_rust::norm::norms;
+use cfd_rust::poisson::PressureSolver;
+use ndarray::prelude::*;
+
+const METHODS: [(Method, f64); 2] = [(Method::BackwardEuler, 1.), (Method::CrankNicolson, 2.)];
+
+#[test]
+fn tridiagonal() {
+    let n = 12;
+    let a = Array::from_shape_fn(n, |ith| -1. - 0.1 * ith as f64);
+    let b = Array::from_shape_fn(n, |ith| 4. + (ith as f64).sin());
+    let c = Array::from_shape_fn(n, |ith| -0.5 + 0.05 * ith as f64);
+    let x = Array::from_shape_fn(n, |ith| (ith as f64).cos());
+    let d = Array::from_shape_fn(n, |ith| {
+        let west = if ith > 0 { a[ith] * x[ith - 1] } else { 0. };
+        let east = if ith < n - 1 { c[ith] * x[ith + 1] } else { 0. };
+        west + b[ith] * x[ith] + east
+    });
+    assert!((thomas(&a, &b, &c, &d) - &x)
+        .iter()
+        .all(|e| e.abs() < 1e-14));
+}
+
+#[test]
+fn periodic() {
+    let n = 12;
+    let a = Array::from_shape_fn(n, |ith| -1. - 0.1 * ith as f64);
+    let b = Array::from_shape_fn(n, |ith| 4. + (ith as f64).sin());
+    let c = Array::from_shape_fn(n, |ith| -0.5 + 0.05 * ith as f64);
+    let x = Array::from_shape_fn(n, |ith| (ith as f64).cos());
+    let d = Array::from_shape_fn(n, |ith| {
+        a[ith] * x[(ith + n - 1) % n] + b[ith] * x[ith] + c[ith] * x[(ith + 1) % n]
+    });
+    assert!((cyclic(&a, &b, &c, &d) - &x)
+        .iter()
+        .all(|e| e.abs() < 1e-14));
+}
+
+#[test]
+fn stability() {
+    // nu dt / dx^2 = 10, fifty times the lesson's value
+    let (u0, nt, dx, _, nu) = x4::default();
+    let dt = 10. * dx.powi(2) / nu;
+    let mut u = u0.clone();
+    x4::solve(&mut u, nt, dx, dt, nu);
+    assert!(u.iter().any(|x| x.abs() > 1e3));
+    for (method, _) in METHODS {
+        let mut u = u0.clone();
+        x4::solve_implicit(&mut u, nt, dx, dt, nu, method);
+        assert!(u.iter().all(|&x| (1. ..=2.).contains(&x)));
+    }
+
+    // nu dt / dx^2 = 20 in 2D, eighty times the lesson's value
+    let (u0, nt, dx, dy, _, nu) = x9::default();
+    let dt = 20. * dx.powi(2) / nu;
+    let mut u = u0.clone();
+    x9::solve(&mut u, nt, dx, dy, dt, nu);
+    assert!(u.iter().any(|x| !x.is_finite() || x.abs() > 1e3));
+    for (method, _) in METHODS {
+        let mut u = u0.clone();
+        x9::solve_implicit(&mut u, nt, dx, dy, dt, nu, method);
+        assert!(u.iter().all(|x| (x - 1.).abs() < 0.1));
+    }
+}
+
+// A narrow bump diffused on a grid with dt = dx, so that nu dt / dx^2 grows with refinement
+fn study1(method: Method) -> Study {
+    let (nu, t) = (0.1, 0.1);
+    let step = TimeStep {
+        sigma: 1.,
+        power: 1,
+    };
+    return convergence::exact_in_time(&convergence::grids(41, 2, 4), 2., step, |n, dx, dt| {
+        let nt = (t / dt).round() as usize;
+        let mut u = Array::from_shape_fn(n, |ith| analytic::gaussian(ith as f64 * dx, 1., 0.1));
+        x4::solve_implicit(&mut u, nt, dx, dt, nu, method);
+        analytic::errors(&u, dx, nt as f64 * dt, |x, t| {
+            analytic::diffused_gaussian(x, t, nu, 1., 0.1)
+        })
+    });
+}
+
+fn study2(method: Method) -> Study {
+    let (nu, t) = (0.1, 0.1);
+    let step = TimeStep {
+        sigma: 0.5,
+        power: 1,
+    };
+    return convergence::exact_in_time(&convergence::grids(21, 2, 4), 2., step, |n, dx, dt| {
+        let nt = (t / dt).round() as usize;
+        let f = |t, x, y| analytic::diffused_gaussian2(x, y, t, nu, 1., 1., 0.15);
+        let mut u = mms::sample(f, 0., (n, n), dx, dx);
+        // x9 takes nt + 1 steps, so it cannot take none
+        if nt > 0 {
+            x9::solve_implicit(&mut u, nt - 1, dx, dx, dt, nu, method);
+        }
+        let e = &u - &mms::sample(f, nt as f64 * dt, (n, n), dx, dx);
+        norms(&e)
+    });
+}
+
+#[test]
+fn orders() {
+    for (method, order) in METHODS {
+        assert!((study1(method).order(|e| e.l2) - order).abs() < 0.15);
+        assert!((study2(method).order(|e| e.l2) - order).abs() < 0.15);
+    }
+}
+
+// Poiseuille flow u = f y (2 - y) / (2 nu) at nu dt / dx^2 = 5, twenty-five times the
+// explicit limit
+#[test]
+fn channel() {
+    let (n, nu, f) = (21, 1., 1.);
+    let dx = 2. / (n - 1) as f64;
+    let run = |dt: f64, eps: f64, viscous| {
+        let (mut u, mut v, mut p) = (
+            Array::zeros((n, n)),
+            Array::zeros((n, n)),
+            Array::zeros((n, n)),
+        );
+        let options = xf::Options {
+            pressure: PressureSolver::Spectral,
+            viscous,
+            ..Default::default()
+        };
+        let nt = xf::solve_with(
+            &mut u, &mut v, &mut p, dx, dx, dt, 1., nu, f, eps, 100_000, &options,
+        );
+        return (u, nt);
+    };
+    let exact = Array::from_shape_fn((n, n), |(j, _)| {
+        let y = j as f64 * dx;
+        f * y * (2. - y) / (2. * nu)
+    });
+    let error = |u: &Array2<f64>| (u - &exact).fold(0., |a: f64, x| a.max(x.abs()));
+
+    let dt = 5. * dx.powi(2) / nu;
+    // With no tolerance the march only stops once the sums overflow to NaN
+    let (u, nt) = run(dt, f64::NEG_INFINITY, Viscous::Explicit);
+    assert_eq!(nt, None);
+    assert!(u.iter().any(|x| !x.is_finite() || x.abs() > 1e3));
+
+    let (u, explicit) = run(dx.powi(2) / (5. * nu), 1e-8, Viscous::Explicit);
+    let explicit = explicit.unwrap();
+    assert!(error(&u) < 1e-3);
+    for (method, _) in METHODS {
+        let (u, implicit) = run(dt, 1e-8, Viscous::Implicit(method));
+        let implicit = implicit.unwrap();
+        assert!(error(&u) < 1e-3);
+        assert!(10 * implicit < explicit);
+    }
+}
+
+// The lid-driven cavity at Re = 20 on 21 x 21 nodes and three times the explicit limit
+// dx^2 / (4 nu) on dt
+#[test]
+fn cavity() {
+    let (n, rho, nu) = (21, 1., 0.1);
+    let dx = 2. / (n - 1) as f64;
+    let run = |dt, viscous| {
+        let (mut u, mut v, mut p) = (
+            Array::zeros((n, n)),
+            Array::zeros((n, n)),
+            Array::zeros((n, n)),
+        );
+        let options = xe::Options {
+            pressure: PressureSolver::Spectral,
+            viscous,
+            ..Default::default()
+        };
+        let nt = xe::steady(
+            &mut u, &mut v, &mut p, dx, dx, dt, rho, nu, 1e-5, 20_000, &options,
+        )
+        .unwrap();
+        return (u, nt);
+    };
+    let dt = 0.075;
+    let (mut u, mut v, mut p) = (
+        Array::zeros((n, n)),
+        Array::zeros((n, n)),
+        Array::zeros((n, n)),
+    );
+    xe::solve_with(
+        &mut u,
+        &mut v,
+        &mut p,
+        200,
+        dx,
+        dx,
+        dt,
+        rho,
+        nu,
+        &Default::default(),
+    );
+    assert!(u.iter().any(|x| !x.is_finite() || x.abs() > 1e3));
+
+    let max = |u: &Array2<f64>| u.fold(0., |a: f64, x| a.max(x.abs()));
+    let (u, explicit) = run(0.01, Viscous::Explicit);
+    for (method, _) in METHODS {
+        // The lesson's steady state depends on dt through the pressure source
+        let (ui, _) = run(0.01, Viscous::Implicit(method));
+        assert!(max(&(&ui - &u)) < 0.02);
+        let (ui, implicit) = run(dt, Viscous::Implicit(method));
+        assert!(max(&ui) <= 1.);
+        assert!(5 * implicit < explicit);
+    }
+}
@@ -0,0 +1,126 @@
+use cfd_rust::inflow::{self, Inlet, Options, Outlet};
+use cfd_rust::mac::Mac;
+use ndarray::prelude::*;
+
+// A 1 x 8 channel with ny cells across
+fn channel(ny: usize, inlet: Inlet, outlet: Outlet, nu: f64) -> Mac {
+    let nx = 8 * ny;
+    let dx = 1. / ny as f64;
+    let mut m = Mac::new(nx, ny, dx, dx);
+    let options = Options {
+        inlet,
+        outlet,
+        ..Default::default()
+    };
+    let divergence = inflow::solve(&mut m, 0.01, 1., nu, 1e-6, 10_000, &options).unwrap();
+    assert!(divergence.iter().all(|d| d.linf < 1e-8));
+    return m;
+}
+
+// Centreline velocity along the channel, between the two middle rows of cells
+fn centreline(m: &Mac) -> Array1<f64> {
+    let r = m.cells().0 / 2;
+    return (&m.u.row(r) + &m.u.row(r + 1)) / 2.;
+}
+
+#[test]
+fn poiseuille() {
+    let nu = 0.05;
+    let m = channel(6, Inlet::Parabolic(1.), Outlet::ZeroGradient, nu);
+    let (ny, nx) = m.cells();
+
+    // What enters leaves through every section
+    let q = inflow::flow_rates(&m);
+    assert!(q.iter().all(|x| (x - q[0]).abs() < 1e-10));
+    // The profile is carried unchanged, to second order
+    let exact = Inlet::Parabolic(1.).profile(ny, m.dy);
+    for c in [nx / 2, nx] {
+        let e = &m.u.slice(s![1..-1, c]) - &exact;
+        assert!(e.iter().all(|e| e.abs() < m.dy.powi(2)));
+    }
+    // dp/dx = -12 mu U / H^2, to second order
+    let r = ny / 2;
+    let dpdx = (m.p[[r, nx - 10]] - m.p[[r, 10]]) / ((nx - 20) as f64 * m.dx);
+    assert!((dpdx / (-12. * nu) - 1.).abs() < 2. * m.dy.powi(2));
+}
+
+#[test]
+fn entrance() {
+    // Re = U H / nu = 20
+    let nu = 0.05;
+    let m = channel(12, Inlet::Uniform(1.), Outlet::Convective, nu);
+    let u = centreline(&m);
+    let developed = u[u.len() - 1];
+    assert!((developed / 1.5 - 1.).abs() < 0.02);
+
+    // Durst et al. (2005): L / H = 0.631 / (1 + 0.044 Re) + 0.0442 Re to 99% of the
+    // developed centreline velocity
+    let re = 1. / nu;
+    let length = 0.631 / (1. + 0.044 * re) + 0.0442 * re;
+    let c = u.iter().position(|&x| x > 0.99 * developed).unwrap();
+    assert!((c as f64 * m.dx - length).abs() < 1.5 * m.dx);
+}
+
+#[test]
+fn outlets() {
+    let nu = 0.05;
+    let reference = channel(12, Inlet::Uniform(1.), Outlet::ZeroGradient, nu);
+    for outlet in [Outlet::Convective, Outlet::Pressure(2.)] {
+        let m = channel(12, Inlet::Uniform(1.), outlet, nu);
+        let e = &m.u - &reference.u;
+        assert!(e.iter().all(|e| e.abs() < 1e-4));
+        let q = inflow::flow_rates(&m);
+        assert!(q.iter().all(|x| (x - 1.).abs() < 1e-10));
+    }
+
+    // The pressure outlet holds its level on the outlet face
+    let m = channel(12, Inlet::Uniform(1.), Outlet::Pressure(2.), nu);
+    let nx = m.cells().1;
+    let face = (&m.p.column(nx) + &m.p.column(nx + 1)) / 2.;
+    assert!(face.slice(s![1..-1]).iter().all(|p| (p - 2.).abs() < 1e-8));
+}
+
+// Flow over the step of Armaly et al. (1983) with an expansion ratio of 2: the inlet
+// covers the upper half of the channel
+fn step_inlet(y: f64, h: f64) -> f64 {
+    if y < h / 2. {
+        return 0.;
+    }
+    return 24. * (y - h / 2.) * (h - y) / h.powi(2);
+}
+
+#[test]
+fn backward_facing_step() {
+    let (nx, ny) = (48, 8);
+    let dx = 1. / ny as f64;
+    let mut m = Mac::new(nx, ny, dx, dx);
+    let options = Options {
+        inlet: Inlet::Function(step_inlet),
+        gamma: 0.5,
+        ..Default::default()
+    };
+    inflow::solve(&mut m, 0.01, 1., 0.02, 1e-5, 10_000, &options).unwrap();
+    let q = inflow::flow_rates(&m);
+    assert!(q.iter().all(|x| (x - q[0]).abs() < 1e-10));
+
+    // The flow separates at the step and reattaches to the bottom wall downstream,
+    // between one and three step heights S = H / 2 at this Reynolds number
+    let bottom = m.u.row(1);
+    assert!(bottom[1] < 0.);
+    let reattachment = bottom.iter().skip(1).position(|&u| u > 0.).unwrap() + 1;
+    let x = reattachment as f64 * dx / 0.5;
+    assert!((1. ..3.).contains(&x));
+    assert!(bottom.iter().skip(reattachment).all(|&u| u > 0.));
+}
+
+#[test]
+fn solve_failures() {
+    let options = Options::default();
+    let run = |dt, max_steps| {
+        let mut m = Mac::new(48, 8, 0.125, 0.125);
+        return inflow::solve(&mut m, dt, 1., 0.05, 1e-6, max_steps, &options);
+    };
+    assert!(run(0.01, 10).is_none());
+    // Far past the explicit viscous limit
+    assert!(run(1., 10_000).is_none());
+}
@@ -0,0 +1,271 @@
+use cfd_rust::lesson::{self, xe};
+use cfd_rust::lesson3;
+use cfd_rust::upwind::Upwinding;
+use cfd_rust::vtk;
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+fn finite(u: &Array3<f64>) -> bool {
+    return u.iter().all(|x| x.is_finite());
+}
+
+// Integral of u - 1 over the cube and its centroid (x, y, z)
+fn excess(u: &Array3<f64>, h: f64) -> (f64, [f64; 3]) {
+    let (mut mass, mut moment) = (0., [0.; 3]);
+    for ((k, j, i), x) in u.indexed_iter() {
+        mass += x - 1.;
+        for (m, n) in moment.iter_mut().zip([i, j, k]) {
+            *m += (x - 1.) * n as f64 * h;
+        }
+    }
+    return (mass * h.powi(3), moment.map(|m| m / mass));
+}
+
+fn max(u: &Array3<f64>) -> f64 {
+    return u.fold(f64::MIN, |a, &x| a.max(x));
+}
+
+fn min(u: &Array3<f64>) -> f64 {
+    return u.fold(f64::MAX, |a, &x| a.min(x));
+}
+
+#[test]
+fn x7() {
+    // Upwinding conserves the hat and moves its centroid by exactly c t along every axis
+    // while it stays clear of the outflow faces
+    let (mut u, _, dx, dy, dz, dt, c) = lesson3::x7::default();
+    let nt = 20;
+    let (mass, centroid) = excess(&u, dx);
+    lesson3::x7::solve(&mut u, nt, dx, dy, dz, dt, c);
+    let (m, x) = excess(&u, dx);
+    assert!((m / mass - 1.).abs() < 1e-6);
+    let t = (nt + 1) as f64 * dt;
+    assert!(x
+        .iter()
+        .zip(centroid)
+        .all(|(x, x0)| (x - x0 - c * t).abs() < 1e-6));
+}
+
+// u = v = w from a hat symmetric in x, y and z stay equal and symmetric, bounded by the
+// initial values, and the hat moves along the diagonal at a speed between 1 and 2
+fn diagonal(u0: &Array3<f64>, u: &Array3<f64>, v: &Array3<f64>, w: &Array3<f64>, t: f64, h: f64) {
+    assert!(u == v && u == w);
+    let transposed = u.view().permuted_axes([2, 1, 0]);
+    assert!((u - &transposed).iter().all(|e| e.abs() < 1e-12));
+    assert!(min(u) > 1. - 1e-5 && max(u) <= 2.);
+
+    let (_, [x, y, z]) = excess(u, h);
+    let (_, [x0, _, _]) = excess(u0, h);
+    assert!((x - y).abs() < 1e-12 && (x - z).abs() < 1e-12);
+    assert!((t..2. * t).contains(&(x - x0)));
+}
+
+#[test]
+fn x8() {
+    let (mut u, mut v, mut w, nt, dx, dy, dz, dt) = lesson3::x8::default();
+    let u0 = u.clone();
+    lesson3::x8::solve(&mut u, &mut v, &mut w, nt, dx, dy, dz, dt);
+    diagonal(&u0, &u, &v, &w, (nt + 1) as f64 * dt, dx);
+}
+
+#[test]
+fn xa() {
+    let (mut u, mut v, mut w, nt, dx, dy, dz, dt, nu) = lesson3::xa::default();
+    let u0 = u.clone();
+    lesson3::xa::solve(&mut u, &mut v, &mut w, nt, dx, dy, dz, dt, nu);
+    diagonal(&u0, &u, &v, &w, (nt + 1) as f64 * dt, dx);
+    // Viscosity rounds off the corners of the hat
+    assert!(max(&u) < 2.);
+}
+
+#[test]
+fn xe() {
+    let (mut u, mut v, mut w, mut p, _, nit, dx, dy, dz, dt, rho, nu) = lesson3::xe::default();
+    let nt = 100;
+    lesson3::xe::solve(
+        &mut u, &mut v, &mut w, &mut p, nt, nit, dx, dy, dz, dt, rho, nu,
+    );
+    assert!(finite(&u) && finite(&v) && finite(&w) && finite(&p));
+
+    let n = u.shape()[0];
+    // By t = 0.1 the end walls have barely reached the mid-span plane, where the flow is
+    // still that of the 2D lesson on the same grid, though they already drive a flow along z
+    let (mut a, mut b, mut q) = (
+        Array::zeros((n, n)),
+        Array::zeros((n, n)),
+        Array::zeros((n, n)),
+    );
+    lesson::xe::solve(&mut a, &mut b, &mut q, nt, nit, dx, dy, dt, rho, nu);
+    let mid = u.index_axis(Axis(0), n / 2);
+    assert!((&mid - &a).iter().all(|e| e.abs() < 1e-3));
+    assert!(w.iter().any(|w| w.abs() > 1e-3));
+}
+
+#[test]
+fn diffusion() {
+    // 1 + sin(pi x / 2) sin(pi y / 2) sin(pi z / 2) keeps the lesson's boundary values and
+    // decays as exp(-3 nu pi^2 t / 4)
+    let (_, _, h, _, _, dt, nu) = lesson3::x9::default();
+    let n = 31;
+    let mode = Array::from_shape_fn((n, n, n), |(k, j, i)| {
+        [i, j, k]
+            .iter()
+            .map(|&m| (PI * m as f64 * h / 2.).sin())
+            .product::<f64>()
+    });
+    let mut u = 1. + &mode;
+    let nt = 99;
+    lesson3::x9::solve(&mut u, nt, h, h, h, dt, nu);
+    let t = (nt + 1) as f64 * dt;
+    let exact = 1. + (-3. * nu * PI.powi(2) / 4. * t).exp() * &mode;
+    assert!((u - exact).iter().all(|e| e.abs() < 2e-3));
+}
+
+#[test]
+fn laplace() {
+    // Insulated z faces leave the 2D solution in every z plane; the lessons' grids on 11
+    // nodes a side
+    let n = 11;
+    let (h, y) = (2. / (n - 1) as f64, Array::linspace(0., 1., n));
+    let mut p = Array::zeros((n, n, n));
+    lesson3::xc::solve(&mut p, y.clone(), h, h, h, 1e-8);
+    let mut q = Array::zeros((n, n));
+    lesson::xc::solve(&mut q, y, h, h, 1e-8);
+    for plane in p.outer_iter() {
+        assert!((&plane - &q).iter().all(|e| e.abs() < 1e-5));
+    }
+}
+
+#[test]
+fn poisson() {
+    // p = sin(pi x / 2) sin(pi y) sin(pi z) on [0, 2] x [0, 1]^2, converged by Jacobi sweeps
+    let error = |n: usize| {
+        let (dx, dy) = (2. / (n as f64 - 1.), 1. / (n as f64 - 1.));
+        let exact = Array::from_shape_fn((n, n, n), |(k, j, i)| {
+            (PI * i as f64 * dx / 2.).sin()
+                * (PI * j as f64 * dy).sin()
+                * (PI * k as f64 * dy).sin()
+        });
+        let b = -(PI.powi(2) / 4. + 2. * PI.powi(2)) * &exact;
+        let mut p = Array::zeros((n, n, n));
+        lesson3::xd::solve(&mut p, b, 20 * n * n, dx, dy, dy);
+        return (p - exact).fold(0., |a: f64, e| a.max(e.abs()));
+    };
+    let (coarse, fine) = (error(9), error(17));
+    assert!(fine < 5e-3);
+    assert!((coarse / fine).log2() > 1.9);
+
+    let (mut p, b, nt, dx, dy, dz) = lesson3::xd::default();
+    lesson3::xd::solve(&mut p, b, nt, dx, dy, dz);
+    assert!(finite(&p));
+}
+
+#[test]
+fn cavity() {
+    // The lesson's cavity at Re = 20 on 11 nodes a side
+    let (n, rho, nu) = (11, 1., 0.1);
+    let (dx, dy, dz) = (0.2, 0.2, 0.2);
+    let mut u = Array::zeros((n, n, n));
+    let (mut v, mut w, mut p) = (u.clone(), u.clone(), u.clone());
+    let options = lesson3::xe::Options {
+        convection: Upwinding::DonorCell,
+        ..Default::default()
+    };
+    let dt = 0.01;
+    lesson3::xe::steady(
+        &mut u, &mut v, &mut w, &mut p, dx, dy, dz, dt, rho, nu, 1e-3, 20_000, &options,
+    )
+    .unwrap();
+
+    // Mirror symmetry about the mid-span plane z = 1, across which w changes sign
+    for k in 0..n {
+        let (a, b) = (u.index_axis(Axis(0), k), u.index_axis(Axis(0), n - 1 - k));
+        assert!((&a - &b).iter().all(|e| e.abs() < 1e-10));
+        let (a, b) = (w.index_axis(Axis(0), k), w.index_axis(Axis(0), n - 1 - k));
+        assert!((&a + &b).iter().all(|e| e.abs() < 1e-10));
+    }
+    // The end walls drive a secondary flow along z
+    assert!(w.iter().any(|w| w.abs() > 1e-2));
+
+    // The mid-span plane is close to the 2D cavity, but slowed by the end walls
+    let mut u2: Array2<f64> = Array::zeros((n, n));
+    let (mut v2, mut p2) = (u2.clone(), u2.clone());
+    let options = xe::Options {
+        convection: Upwinding::DonorCell,
+        ..Default::default()
+    };
+    xe::steady(
+        &mut u2, &mut v2, &mut p2, dx, dy, dt, rho, nu, 1e-3, 20_000, &options,
+    )
+    .unwrap();
+    let mid = u.index_axis(Axis(0), n / 2);
+    assert!((&mid - &u2).iter().all(|e| e.abs() < 0.03));
+    let minimum = |c: ArrayView1<f64>| c.fold(0., |a: f64, &x| a.min(x));
+    assert!(minimum(mid.column(n / 2)) > minimum(u2.column(n / 2)));
+
+    // VTK output of the whole cavity
+    let mut out = Vec::new();
+    vtk::write(
+        &mut out,
+        "cavity",
+        (dx, dy, dz),
+        &[("p", p.view())],
+        &[("velocity", &[u.view(), v.view(), w.view()])],
+    )
+    .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[4], format!("DIMENSIONS {n} {n} {n}"));
+    assert_eq!(lines[7], format!("POINT_DATA {}", n.pow(3)));
+    assert_eq!(lines.len(), 8 + 2 + n.pow(3) + 1 + n.pow(3));
+    // The lid node next to the corner (x, y, z) = (dx, 2, dz)
+    let index = 8 + 2 + n.pow(3) + 1 + (n * n + (n - 1) * n + 1);
+    assert_eq!(lines[index], "1 0 0");
+}
+
+#[test]
+fn steady_failures() {
+    let (u, _, _, _, _, _, dx, dy, dz, _, rho, nu) = lesson3::xe::default();
+    let run = |dt, max_steps| {
+        let (mut u, mut v, mut w, mut p) = (u.clone(), u.clone(), u.clone(), u.clone());
+        let options = lesson3::xe::Options::default();
+        return lesson3::xe::steady(
+            &mut u, &mut v, &mut w, &mut p, dx, dy, dz, dt, rho, nu, 1e-3, max_steps, &options,
+        );
+    };
+    assert_eq!(run(0.001, 5), None);
+    assert_eq!(run(1., 1000), None);
+}
+
+#[test]
+fn vtk() {
+    let u = Array::from_shape_fn((3, 4), |(j, i)| (10 * j + i) as f64);
+    let mut out = Vec::new();
+    vtk::write(
+        &mut out,
+        "plane",
+        (0.5, 0.25, 1.),
+        &[("u", u.view())],
+        &[("velocity", &[u.view(), u.view()])],
+    )
+    .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[4], "DIMENSIONS 4 3 1");
+    assert_eq!(lines[6], "SPACING 0.5 0.25 1");
+    // x varies fastest
+    assert_eq!(&lines[10..14], &["0", "1", "2", "3"]);
+    assert_eq!(lines[14], "10");
+    assert_eq!(lines[23], "0 0 0");
+    assert_eq!(lines[24], "1 1 0");
+
+    let v = Array::zeros((4, 3));
+    let mismatched = vtk::write(
+        Vec::new(),
+        "",
+        (1., 1., 1.),
+        &[("u", u.view()), ("v", v.view())],
+        &[],
+    );
+    assert!(mismatched.is_err());
+}
@@ -0,0 +1,76 @@
+use cfd_rust::benchmark;
+use cfd_rust::lesson::xe::{self, Options, PressureBc};
+use cfd_rust::mac::{self, Mac};
+use cfd_rust::poisson::PressureSolver;
+use ndarray::prelude::*;
+
+#[test]
+fn interpolation() {
+    let (nx, ny, dx, dy) = (6, 4, 0.5, 0.25);
+    let f = |x: f64, y: f64| 1. + 2. * x - 3. * y;
+    let mut m = Mac::new(nx, ny, dx, dy);
+    m.u = Array::from_shape_fn(m.u.raw_dim(), |(r, c)| {
+        f(c as f64 * dx, (r as f64 - 0.5) * dy)
+    });
+    m.v = Array::from_shape_fn(m.v.raw_dim(), |(r, c)| {
+        f((c as f64 - 0.5) * dx, r as f64 * dy)
+    });
+    m.p = Array::from_shape_fn(m.p.raw_dim(), |(r, c)| {
+        f((c as f64 - 0.5) * dx, (r as f64 - 0.5) * dy)
+    });
+    let (u, v, p) = m.nodes();
+    let nodes = Array::from_shape_fn((ny + 1, nx + 1), |(j, i)| f(i as f64 * dx, j as f64 * dy));
+    for w in [u, v, p] {
+        assert!((w - &nodes).iter().all(|e| e.abs() < 1e-12));
+    }
+    let (u, v) = m.centres();
+    let centres = Array::from_shape_fn((ny, nx), |(j, i)| {
+        f((i as f64 + 0.5) * dx, (j as f64 + 0.5) * dy)
+    });
+    for w in [u, v] {
+        assert!((w - &centres).iter().all(|e| e.abs() < 1e-12));
+    }
+    // div(u, v) = 2 - 3
+    assert!(m.divergence().iter().all(|d| (d + 1.).abs() < 1e-12));
+}
+
+#[test]
+fn ghia() {
+    let (u, _, _, _, _, dx, dy, _, rho, _) = xe::default();
+    let (ny, nx) = (u.nrows() - 1, u.ncols() - 1);
+    let mut m = Mac::new(nx, ny, dx, dy);
+    let nu = benchmark::cavity_nu(100);
+    let divergence = mac::cavity(&mut m, 1500, 0.01, rho, nu, 1., &Default::default());
+    assert!(divergence.iter().all(|d| d.linf < 1e-9));
+    let (um, vm, _) = m.nodes();
+    let (eu, ev) = benchmark::cavity(&um, &vm, 100).unwrap();
+    assert!(eu.linf < 0.01 && ev.linf < 0.01);
+
+    // The collocated lesson on the same nodes
+    let options = Options {
+        pressure: PressureSolver::Spectral,
+        pressure_bc: PressureBc::ZeroMean,
+        ..Default::default()
+    };
+    let (u, v, _, _, _) = benchmark::xe_cavity(100, &options).unwrap();
+    let (cu, cv) = benchmark::cavity(&u, &v, 100).unwrap();
+    assert!(eu.l2 < cu.l2 && ev.l2 < cv.l2);
+}
+
+// Poiseuille flow u = f y (2 - y) / (2 nu) at the cell centres
+#[test]
+fn channel() {
+    let (nx, ny, nu, f) = (20, 20, 0.1, 1.);
+    let (dx, dy) = (2. / nx as f64, 2. / ny as f64);
+    let mut m = Mac::new(nx, ny, dx, dy);
+    let divergence = mac::channel(&mut m, 0.01, 1., nu, f, 1e-8, &Default::default());
+    assert!(divergence.iter().all(|d| d.linf < 1e-9));
+    let (u, v) = m.centres();
+    let exact = Array::from_shape_fn((ny, nx), |(j, _)| {
+        let y = (j as f64 + 0.5) * dy;
+        f * y * (2. - y) / (2. * nu)
+    });
+    // The ghost values are exact for a linear profile, so the wall error is O(dy^2)
+    assert!((u - &exact).iter().all(|e| e.abs() < 2. * dy.powi(2)));
+    assert!(v.iter().all(|x| x.abs() < 1e-12));
+}
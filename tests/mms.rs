@@ -0,0 +1,90 @@
+use cfd_rust::convergence::{self, TimeStep};
+use cfd_rust::lesson::xe::{Options, PressureBc};
+use cfd_rust::mms::{self, Field};
+use cfd_rust::poisson::PressureSolver;
+use std::f64::consts::PI;
+
+#[test]
+fn x9() {
+    let u =
+        Field::new(|t: f64, x: f64, y: f64| 1. + (-t).exp() * (PI * x / 2.).sin() * (PI * y).sin());
+    // sigma = nu dt / dx^2 = 0.25
+    let step = TimeStep {
+        sigma: 0.25 / 0.05,
+        power: 2,
+    };
+    let study = convergence::exact_in_time(&convergence::grids(21, 2, 3), 2., step, |n, _, dt| {
+        mms::x9(&u, 0.05, 0.5, n, dt)
+    });
+    assert!((study.order(|e| e.l2) - 2.).abs() < 0.1);
+}
+
+#[test]
+fn xa() {
+    let u = Field::new(|t: f64, x: f64, y: f64| {
+        1. + t.cos() * (PI * x / 2.).sin() * (PI * y / 2.).sin()
+    });
+    let v = Field::new(|t: f64, x: f64, y: f64| {
+        1. + 0.5 * (1. + t) * (PI * x).sin() * (PI * y / 2.).sin()
+    });
+    let step = TimeStep {
+        sigma: 1.,
+        power: 2,
+    };
+    let study = convergence::exact_in_time(&convergence::grids(21, 2, 3), 2., step, |n, _, dt| {
+        mms::xa(&u, &v, 0.1, 0.2, n, dt)
+    });
+    assert!((study.order(|e| e.l2) - 1.).abs() < 0.15);
+}
+
+#[test]
+fn xd() {
+    let p = Field::new(|_, x: f64, y: f64| (PI * x / 2.).sin() * (PI * y).sin() * (1. + x));
+    let study = convergence::exact(&convergence::grids(9, 2, 3), |n| mms::xd(&p, 10 * n * n, n));
+    assert!((study.order(|e| e.l2) - 2.).abs() < 0.1);
+}
+
+// A divergence-free flow that vanishes on the walls, with dp/dn = 0
+fn cavity(pressure: PressureSolver) -> f64 {
+    let u = Field::new(|_, x: f64, y: f64| PI / 2. * (PI * x / 2.).sin().powi(2) * (PI * y).sin());
+    let v = Field::new(|_, x: f64, y: f64| -PI / 2. * (PI * x).sin() * (PI * y / 2.).sin().powi(2));
+    let p = Field::new(|_, x: f64, y: f64| (PI * x).cos() * (PI * y).cos());
+    let options = Options {
+        pressure,
+        pressure_bc: PressureBc::ZeroMean,
+        ..Default::default()
+    };
+    let step = TimeStep {
+        sigma: 0.2 / 0.1,
+        power: 2,
+    };
+    let study = convergence::exact_in_time(&convergence::grids(21, 2, 3), 2., step, |n, _, dt| {
+        mms::xe(&u, &v, &p, 1., 0.1, 0.2, n, dt, &options)
+    });
+    return study.order(|e| e.l2);
+}
+
+#[test]
+fn xe() {
+    assert!((cavity(PressureSolver::Jacobi(200)) - 1.).abs() < 0.15);
+    assert!((cavity(PressureSolver::Spectral) - 1.).abs() < 0.15);
+}
+
+// Poiseuille flow plus a periodic, divergence-free perturbation
+#[test]
+fn xf() {
+    let u = Field::new(|_, x: f64, y: f64| {
+        y * (2. - y) + PI / 2. * (1. + 0.5 * (PI * x).cos()) * (PI * y).sin()
+    });
+    let v = Field::new(|_, x: f64, y: f64| PI / 2. * (PI * x).sin() * (PI * y / 2.).sin().powi(2));
+    let p = Field::new(|_, x: f64, y: f64| (PI * x).cos() * (PI * y).cos());
+    // dt from the wall-normal spacing dy
+    let step = TimeStep {
+        sigma: 0.1 / 0.5,
+        power: 2,
+    };
+    let study = convergence::exact_in_time(&convergence::grids(11, 2, 3), 2., step, |n, _, dt| {
+        mms::xf(&u, &v, &p, 1., 0.5, 1e-12, n, dt, PressureSolver::Spectral).unwrap()
+    });
+    assert!((study.order(|e| e.l2) - 1.).abs() < 0.15);
+}
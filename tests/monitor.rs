@@ -0,0 +1,201 @@
+use cfd_rust::lesson::{x1, x5, x9, xa, xe, xf};
+use cfd_rust::monitor::{self, Monitor, Options, Probe};
+use cfd_rust::poisson::PressureSolver;
+use cfd_rust::post;
+use ndarray::prelude::*;
+
+// Header and rows of the CSV written by a monitor
+fn parse(csv: Vec<u8>) -> (Vec<String>, Vec<Vec<f64>>) {
+    let text = String::from_utf8(csv).unwrap();
+    let mut lines = text.lines();
+    let header = lines.next().unwrap().split(',').map(String::from).collect();
+    let rows = lines
+        .map(|l| {
+            l.split(',')
+                .map(|x| x.parse().unwrap_or(f64::NAN))
+                .collect()
+        })
+        .collect();
+    return (header, rows);
+}
+
+fn column(header: &[String], name: &str) -> usize {
+    return header.iter().position(|h| h == name).unwrap();
+}
+
+#[test]
+fn interpolation() {
+    // Bilinear fields are reproduced between the nodes, and x wraps when periodic
+    let (dx, dy) = (0.5, 0.25);
+    let f = Array::from_shape_fn((5, 4), |(j, i)| {
+        let (x, y) = (i as f64 * dx, j as f64 * dy);
+        1. + 2. * x - y + x * y
+    });
+    for (x, y) in [(0.3, 0.6), (1.5, 1.), (0., 0.), (1.2, 0.1)] {
+        let exact = 1. + 2. * x - y + x * y;
+        let value = monitor::interpolate(&f.view(), dx, dy, x, y, false);
+        assert!((value - exact).abs() < 1e-12);
+    }
+    let wrapped = monitor::interpolate(&f.view(), dx, dy, 2. + 0.3, 0.6, true);
+    assert!((wrapped - monitor::interpolate(&f.view(), dx, dy, 0.3, 0.6, true)).abs() < 1e-12);
+    let clamped = monitor::interpolate(&f.view(), dx, dy, 5., 0., false);
+    assert_eq!(clamped, f[[0, 3]]);
+}
+
+#[test]
+fn linear() {
+    // Probes at the nodes of x1 read its field, and rows follow `every`
+    let (u0, nt, dx, dt, c) = x1::default();
+    let mut reference = u0.clone();
+    x1::solve(&mut reference, nt, dx, dt, c);
+
+    let options = Options {
+        every: 5,
+        probes: vec![Probe {
+            name: String::from("middle"),
+            x: 20. * dx,
+            y: 0.,
+        }],
+        ..Default::default()
+    };
+    let mut monitor = Monitor::new(Vec::new(), options);
+    let mut u = u0.clone();
+    x1::solve_monitored(&mut u, nt, dx, dt, c, &mut monitor).unwrap();
+    assert_eq!(u, reference);
+
+    let (header, rows) = parse(monitor.into_inner());
+    assert_eq!(
+        header,
+        ["step", "t", "middle.u", "kinetic_energy", "residual.u"]
+    );
+    assert_eq!(rows.len(), nt / 5 + 1);
+    assert!(rows[0][4].is_nan());
+    let last = rows.last().unwrap();
+    assert_eq!(last[0] as usize, nt);
+    assert_eq!(last[2], reference[20]);
+    let energy =
+        reference.mapv(|u| u * u / 2.).sum() - (reference[0].powi(2) + reference[40].powi(2)) / 4.;
+    assert!((last[3] - energy * dx).abs() < 1e-12);
+}
+
+#[test]
+fn diffusion() {
+    // The residual of the periodic Burgers lesson and of 2D diffusion decays
+    let (mut u, nt, dx, dt, nu) = x5::default();
+    let mut monitor = Monitor::new(Vec::new(), Options::default());
+    x5::solve_monitored(&mut u, nt, dx, dt, nu, &mut monitor).unwrap();
+    let (header, rows) = parse(monitor.into_inner());
+    assert_eq!(rows.len(), nt + 1);
+    let energy = column(&header, "kinetic_energy");
+    assert!(rows.windows(2).all(|r| r[1][energy] <= r[0][energy]));
+
+    let (u0, nt, dx, dy, dt, nu) = x9::default();
+    let mut reference = u0.clone();
+    x9::solve(&mut reference, nt, dx, dy, dt, nu);
+    let mut monitor = Monitor::new(Vec::new(), Options::default());
+    let mut u = u0.clone();
+    x9::solve_monitored(&mut u, nt, dx, dy, dt, nu, &mut monitor).unwrap();
+    assert_eq!(u, reference);
+    let (header, rows) = parse(monitor.into_inner());
+    assert_eq!(rows.len(), nt + 2);
+    let residual = column(&header, "residual.u");
+    assert!(rows[1][residual] > rows[nt + 1][residual]);
+}
+
+#[test]
+fn burgers() {
+    let (u0, v0, nt, dx, dy, dt, nu) = xa::default();
+    let (mut u, mut v) = (u0.clone(), v0.clone());
+    let mut monitor = Monitor::new(Vec::new(), Options::default());
+    xa::solve_monitored(&mut u, &mut v, nt, dx, dy, dt, nu, &mut monitor).unwrap();
+    let (header, rows) = parse(monitor.into_inner());
+    let last = rows.last().unwrap();
+    let divergence = post::divergence(&u, &v, dx, dy, false);
+    let linf = divergence.fold(0., |a: f64, x| a.max(x.abs()));
+    assert_eq!(last[column(&header, "max_divergence")], linf);
+    let energy = post::kinetic_energy(&u, &v, dx, dy, false);
+    assert!((last[column(&header, "kinetic_energy")] - energy).abs() < 1e-12);
+}
+
+#[test]
+fn cavity() {
+    let (mut u, mut v, mut p, nt, _, dx, dy, dt, rho, nu) = xe::default();
+    let options = Options {
+        every: 10,
+        probes: vec![Probe {
+            name: String::from("centre"),
+            x: 1.,
+            y: 1.,
+        }],
+        ..Default::default()
+    };
+    let mut monitor = Monitor::new(Vec::new(), options);
+    let xe_options = xe::Options::default();
+    xe::solve_monitored(
+        &mut u,
+        &mut v,
+        &mut p,
+        nt,
+        dx,
+        dy,
+        dt,
+        rho,
+        nu,
+        &xe_options,
+        &mut monitor,
+    )
+    .unwrap();
+    let (header, rows) = parse(monitor.into_inner());
+    assert_eq!(rows.len(), nt / 10 + 1);
+    let last = rows.last().unwrap();
+    // (1, 1) is the middle node
+    assert_eq!(last[column(&header, "centre.u")], u[[20, 20]]);
+    assert_eq!(last[column(&header, "centre.p")], p[[20, 20]]);
+    let energy = column(&header, "kinetic_energy");
+    assert_eq!(rows[0][energy], 0.);
+    assert!(last[energy] > rows[1][energy]);
+    let residual = column(&header, "residual.u");
+    assert!(last[residual] < rows[1][residual]);
+}
+
+#[test]
+fn channel() {
+    // The flux is the same through every section once the flow is developed
+    let (mut u, mut v, mut p, nit, dx, dy, dt, rho, nu, f, eps) = xf::default();
+    let options = Options {
+        sections: vec![0.5, 1.25],
+        ..Default::default()
+    };
+    let mut monitor = Monitor::new(Vec::new(), options);
+    let xf_options = xf::Options {
+        pressure: PressureSolver::Jacobi(nit),
+        ..Default::default()
+    };
+    let steps = xf::solve_monitored(
+        &mut u,
+        &mut v,
+        &mut p,
+        dx,
+        dy,
+        dt,
+        rho,
+        nu,
+        f,
+        eps,
+        100_000,
+        &xf_options,
+        &mut monitor,
+    )
+    .unwrap()
+    .unwrap();
+    let (header, rows) = parse(monitor.into_inner());
+    assert_eq!(rows.len(), steps + 1);
+    let last = rows.last().unwrap();
+    let (a, b) = (
+        last[column(&header, "flux(x=0.5)")],
+        last[column(&header, "flux(x=1.25)")],
+    );
+    assert!(a > 0. && (a / b - 1.).abs() < 1e-2);
+    let psi = post::streamfunction(&u, &v, dx, dy);
+    assert!((a / psi[[u.nrows() - 1, 10]] - 1.).abs() < 1e-2);
+}
@@ -0,0 +1,131 @@
+use cfd_rust::channel;
+use cfd_rust::implicit::{Method, Viscous};
+use cfd_rust::lesson::{xe, xf};
+use cfd_rust::obstacle::{self, Forcing};
+use cfd_rust::poisson::PressureSolver;
+use cfd_rust::upwind::Upwinding;
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+const FORCINGS: [Forcing; 2] = [Forcing::StairStep, Forcing::DirectForcing];
+
+// Steady channel flow around `alpha`, stable at the time step of xf
+fn channel(alpha: &Array2<f64>, forcing: Forcing) -> (Array2<f64>, Array2<f64>, f64) {
+    let (mut u, mut v, mut p, _, dx, dy, dt, rho, nu, f, _) = xf::default();
+    let options = xf::Options {
+        pressure: PressureSolver::Spectral,
+        convection: Upwinding::DonorCell,
+        viscous: Viscous::Implicit(Method::CrankNicolson),
+    };
+    xf::solve_masked(
+        &mut u, &mut v, &mut p, dx, dy, dt, rho, nu, f, 1e-6, 100_000, &options, alpha, forcing,
+    )
+    .unwrap();
+    return (u, v, dy);
+}
+
+#[test]
+fn shapes() {
+    let (u, _, _, _, dx, dy, _, _, _, _, _) = xf::default();
+    let area = |alpha: Array2<f64>| alpha.sum() * dx * dy;
+    let circle = obstacle::fraction(u.dim(), dx, dy, obstacle::circle(1., 1., 0.3));
+    assert!((area(circle.clone()) / (PI * 0.09) - 1.).abs() < 1e-2);
+    assert!(circle.iter().all(|a| (0. ..=1.).contains(a)));
+    assert_eq!(circle[[20, 20]], 1.);
+    // Edges halfway between the nodes cut their cells exactly
+    let rectangle = obstacle::rectangle(0.5, 0.5, 1., 1.25);
+    assert!((area(obstacle::fraction(u.dim(), dx, dy, rectangle)) - 0.375).abs() < 1e-12);
+}
+
+#[test]
+fn pbm() {
+    let image = "P1\n# a step\n4 3\n0 0 0 0\n0 0 0 0\n1 1 0 0\n";
+    let solid = obstacle::pbm(image).unwrap();
+    assert_eq!(solid.dim(), (3, 4));
+    // The last image row is the bottom of the grid
+    assert!(solid[[0, 0]] && solid[[0, 1]] && !solid[[0, 2]] && !solid[[2, 0]]);
+    assert_eq!(obstacle::mask(&solid).sum(), 2.);
+    assert_eq!(
+        obstacle::pbm("P1 4 3 000000000000110").map(|s| s.dim()),
+        None
+    );
+    assert_eq!(obstacle::pbm("P2 1 1 1"), None);
+    assert_eq!(obstacle::pbm("P1 2 1 02"), None);
+    assert_eq!(obstacle::pbm("P1 2 1 0 x"), None);
+    assert_eq!(obstacle::pbm("P1 2 1 01").unwrap(), array![[false, true]]);
+}
+
+#[test]
+fn cylinder() {
+    let (u0, _, _, _, dx, dy, _, _, nu, f, _) = xf::default();
+    let alpha = obstacle::fraction(u0.dim(), dx, dy, obstacle::circle(1., 1., 0.3));
+    let empty = channel::flow_rate(&channel::poiseuille(&u0, dy, nu, f), dy);
+    let mut rates = Vec::new();
+    for forcing in FORCINGS {
+        let (u, v, dy) = channel(&alpha, forcing);
+        // At rest inside, and mirrored about the centreline of the channel
+        assert!(u.iter().zip(&alpha).all(|(u, a)| *a < 1. || *u == 0.));
+        assert!((&u - &u.slice(s![..;-1, ..]))
+            .iter()
+            .all(|e| e.abs() < 1e-10));
+        assert!((&v + &v.slice(s![..;-1, ..]))
+            .iter()
+            .all(|e| e.abs() < 1e-10));
+        let q = channel::flow_rate(&u, dy);
+        assert!(q > 0. && q < 0.25 * empty);
+        rates.push(q);
+    }
+    assert!((rates[0] / rates[1] - 1.).abs() < 0.1);
+}
+
+#[test]
+fn rib() {
+    // A rib on the lower wall: the flow separates and recirculates behind it
+    let (u0, _, _, _, dx, dy, _, _, _, _, _) = xf::default();
+    let alpha = obstacle::fraction(u0.dim(), dx, dy, obstacle::rectangle(0.8, 0., 1., 0.6));
+    for forcing in FORCINGS {
+        let (u, _, _) = channel(&alpha, forcing);
+        assert!(channel::flow_rate(&u, dy) > 0.);
+        let behind = u.slice(s![1..12, 21..]);
+        assert!(behind.iter().any(|u| *u < 0.));
+    }
+}
+
+#[test]
+fn cavity() {
+    // A block drawn as an image in the middle of the cavity
+    let (mut u, mut v, mut p, nt, _, dx, dy, dt, rho, nu) = xe::default();
+    let (ny, nx) = u.dim();
+    let mut image = format!("P1 {} {}\n", nx, ny);
+    for j in 0..ny {
+        for i in 0..nx {
+            let inside = (15..=25).contains(&i) && (15..=25).contains(&j);
+            image.push(if inside { '1' } else { '0' });
+        }
+        image.push('\n');
+    }
+    let alpha = obstacle::mask(&obstacle::pbm(&image).unwrap());
+    let options = xe::Options::default();
+    xe::solve_masked(
+        &mut u,
+        &mut v,
+        &mut p,
+        nt,
+        dx,
+        dy,
+        dt,
+        rho,
+        nu,
+        &options,
+        &alpha,
+        Forcing::StairStep,
+    );
+    assert!(u
+        .iter()
+        .chain(v.iter())
+        .all(|x| x.is_finite() && x.abs() <= 1.));
+    assert!(u.slice(s![15..=25, 15..=25]).iter().all(|u| *u == 0.));
+    assert!(v.slice(s![15..=25, 15..=25]).iter().all(|v| *v == 0.));
+    // The lid still drives the flow over the block
+    assert!(u[[35, 20]] > 0.1);
+}
@@ -0,0 +1,172 @@
+use cfd_rust::lesson;
+use cfd_rust::lesson::xe::PressureBc;
+use cfd_rust::poisson::{self, Boundary, PressureSolver};
+use ndarray::prelude::*;
+
+fn laplacian(p: &Array2<f64>, dx: f64, dy: f64, x: Boundary, y: Boundary) -> Array2<f64> {
+    let (ny, nx) = p.dim();
+    let neighbour = |ith: isize, n: usize, boundary: Boundary| -> usize {
+        return match boundary {
+            Boundary::Periodic => ith.rem_euclid(n as isize) as usize,
+            Boundary::Neumann if ith < 0 => 1,
+            Boundary::Neumann if ith >= n as isize => n - 2,
+            Boundary::NeumannDirichlet if ith < 0 => 1,
+            _ => ith as usize,
+        };
+    };
+    let mut b = Array::zeros((ny, nx));
+    for j in 0..ny {
+        for i in 0..nx {
+            if (x == Boundary::Dirichlet && (i == 0 || i == nx - 1))
+                || (y == Boundary::Dirichlet && (j == 0 || j == ny - 1))
+                || (x == Boundary::NeumannDirichlet && i == nx - 1)
+                || (y == Boundary::NeumannDirichlet && j == ny - 1)
+            {
+                continue;
+            }
+            let (w, e) = (
+                neighbour(i as isize - 1, nx, x),
+                neighbour(i as isize + 1, nx, x),
+            );
+            let (s, n) = (
+                neighbour(j as isize - 1, ny, y),
+                neighbour(j as isize + 1, ny, y),
+            );
+            b[[j, i]] = (p[[j, e]] - 2. * p[[j, i]] + p[[j, w]]) / dx.powi(2)
+                + (p[[n, i]] - 2. * p[[j, i]] + p[[s, i]]) / dy.powi(2);
+        }
+    }
+    return b;
+}
+
+fn check(x: Boundary, y: Boundary) {
+    let (nx, ny) = (33, 24);
+    let (dx, dy) = (2. / (nx as f64 - 1.), 1. / (ny as f64 - 1.));
+    let mut exact = Array::from_shape_fn((ny, nx), |(j, i)| {
+        let (x, y) = (i as f64 * dx, j as f64 * dy);
+        return (3. * x).sin() * (2. * y).cos() + x * y.powi(2);
+    });
+    if x == Boundary::Dirichlet {
+        exact.column_mut(0).fill(0.);
+        exact.column_mut(nx - 1).fill(0.);
+    }
+    if y == Boundary::Dirichlet {
+        exact.row_mut(0).fill(0.);
+        exact.row_mut(ny - 1).fill(0.);
+    }
+    if x == Boundary::NeumannDirichlet {
+        exact.column_mut(nx - 1).fill(0.);
+    }
+    if y == Boundary::NeumannDirichlet {
+        exact.row_mut(ny - 1).fill(0.);
+    }
+    let b = laplacian(&exact, dx, dy, x, y);
+
+    let mut p = Array::zeros((ny, nx));
+    poisson::spectral(&mut p, &b, dx, dy, x, y);
+
+    // Singular cases are only defined up to a constant
+    let singular = |b| b == Boundary::Periodic || b == Boundary::Neumann;
+    let offset = if singular(x) && singular(y) {
+        (&exact - &p).mean().unwrap()
+    } else {
+        0.
+    };
+    let error = (&exact - &p - offset)
+        .mapv(f64::abs)
+        .fold(0., |a: f64, &b| a.max(b));
+    assert!(error < 1e-9, "{:?}/{:?}: {}", x, y, error);
+}
+
+#[test]
+fn dirichlet() {
+    check(Boundary::Dirichlet, Boundary::Dirichlet);
+}
+
+#[test]
+fn periodic() {
+    check(Boundary::Periodic, Boundary::Periodic);
+}
+
+#[test]
+fn channel() {
+    check(Boundary::Periodic, Boundary::Neumann);
+}
+
+#[test]
+fn neumann() {
+    check(Boundary::Neumann, Boundary::Neumann);
+}
+
+#[test]
+fn lid() {
+    check(Boundary::Neumann, Boundary::NeumannDirichlet);
+    check(Boundary::NeumannDirichlet, Boundary::Periodic);
+}
+
+#[test]
+fn xd() {
+    let (mut p, b, _, dx, dy) = lesson::xd::default();
+    let mut q = p.clone();
+    poisson::dirichlet(&mut p, &b, dx, dy);
+    lesson::xd::solve(&mut q, b, 5000, dx, dy);
+    assert!((&p - &q).mapv(f64::abs).sum() / p.mapv(f64::abs).sum() < 1e-3);
+}
+
+#[test]
+fn xe() {
+    let (mut u, mut v, mut p, _, _, dx, dy, dt, rho, nu) = lesson::xe::default();
+    let options = lesson::xe::Options {
+        pressure: PressureSolver::Spectral,
+        ..Default::default()
+    };
+    lesson::xe::solve_with(&mut u, &mut v, &mut p, 100, dx, dy, dt, rho, nu, &options);
+    assert!(u
+        .iter()
+        .chain(v.iter())
+        .chain(p.iter())
+        .all(|x| x.is_finite()));
+}
+
+#[test]
+fn xe_lid() {
+    // p = 0 along the lid changes the solution, not only its level
+    let (u0, v0, p0, _, _, dx, dy, dt, rho, nu) = lesson::xe::default();
+    let run = |pressure_bc| {
+        let (mut u, mut v, mut p) = (u0.clone(), v0.clone(), p0.clone());
+        let options = lesson::xe::Options {
+            pressure: PressureSolver::Spectral,
+            pressure_bc,
+            ..Default::default()
+        };
+        lesson::xe::solve_with(&mut u, &mut v, &mut p, 10, dx, dy, dt, rho, nu, &options);
+        return p;
+    };
+    let lid = run(PressureBc::Lid);
+    let neumann = run(PressureBc::ZeroMean);
+    assert!(lid.row(lid.nrows() - 1).iter().all(|&x| x == 0.));
+    let difference = &lid - &neumann;
+    let offset = difference.mean().unwrap();
+    let largest = neumann.fold(0., |a: f64, x| a.max(x.abs()));
+    assert!(difference
+        .iter()
+        .any(|d| (d - offset).abs() > 0.1 * largest));
+}
+
+#[test]
+fn xf() {
+    let (mut u, mut v, mut p, _, dx, dy, dt, rho, nu, f, eps) = lesson::xf::default();
+    let options = lesson::xf::Options {
+        pressure: PressureSolver::Spectral,
+        ..Default::default()
+    };
+    lesson::xf::solve_with(
+        &mut u, &mut v, &mut p, dx, dy, dt, rho, nu, f, eps, 100_000, &options,
+    )
+    .unwrap();
+    assert!(u
+        .iter()
+        .chain(v.iter())
+        .chain(p.iter())
+        .all(|x| x.is_finite()));
+}
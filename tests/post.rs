@@ -0,0 +1,103 @@
+use cfd_rust::lesson::{xe, xf};
+use cfd_rust::poisson::PressureSolver;
+use cfd_rust::post;
+use cfd_rust::vorticity;
+use ndarray::prelude::*;
+
+fn grid() -> (usize, usize, f64, f64) {
+    let (u, _, _, _, _, dx, dy, _, _, _) = xe::default();
+    let (ny, nx) = u.dim();
+    return (ny, nx, dx, dy);
+}
+
+#[test]
+fn rotation() {
+    // Solid-body rotation about the centre of the 2 x 2 square
+    let (ny, nx, dx, dy) = grid();
+    let u = Array::from_shape_fn((ny, nx), |(j, _)| -(j as f64 * dy - 1.));
+    let v = Array::from_shape_fn((ny, nx), |(_, i)| i as f64 * dx - 1.);
+    let post = post::post(&u, &v, dx, dy, false);
+
+    assert!(post.vorticity.iter().all(|w| (w - 2.).abs() < 1e-12));
+    assert!(post.divergence.iter().all(|d| d.abs() < 1e-12));
+    assert!(post.q.iter().all(|q| (q - 1.).abs() < 1e-12));
+    let exact = Array::from_shape_fn((ny, nx), |(j, i)| {
+        let (x, y) = (i as f64 * dx, j as f64 * dy);
+        1. - ((x - 1.).powi(2) + (y - 1.).powi(2)) / 2.
+    });
+    assert!((&post.streamfunction - &exact)
+        .iter()
+        .all(|e| e.abs() < 1e-12));
+    assert!((post.magnitude[[0, 0]] - 2f64.sqrt()).abs() < 1e-12);
+    // 4 / 3 up to the O(dx^2) error of the trapezoidal rule
+    assert!((post.kinetic_energy - 4. / 3.).abs() < 1e-2);
+    assert!((post.enstrophy - 8.).abs() < 1e-12);
+}
+
+#[test]
+fn poiseuille() {
+    // u = y (2 - y), periodic in x as in xf
+    let (ny, nx, dx, dy) = grid();
+    let u = Array::from_shape_fn((ny, nx), |(j, _)| {
+        let y = j as f64 * dy;
+        y * (2. - y)
+    });
+    let v = Array::zeros((ny, nx));
+    let post = post::post(&u, &v, dx, dy, true);
+
+    for ((j, _), w) in post.vorticity.indexed_iter() {
+        assert!((w - (2. * j as f64 * dy - 2.)).abs() < 1e-12);
+    }
+    assert!(post.divergence.iter().all(|d| d.abs() < 1e-12));
+    assert!(post.q.iter().all(|q| q.abs() < 1e-12));
+    // The flux through the channel, 4 / 3, is psi on the top wall
+    let top = post.streamfunction.row(ny - 1);
+    assert!(top.iter().all(|psi| (psi - 4. / 3.).abs() < 1e-3));
+    // Without end weights along x the integrals cover nx dx
+    let length = nx as f64 * dx;
+    assert!((post.kinetic_energy / length - 8. / 15.).abs() < 1e-2);
+    assert!((post.enstrophy / length - 4. / 3.).abs() < 1e-2);
+}
+
+#[test]
+fn cavity() {
+    let (mut u, mut v, mut p, _, _, dx, dy, _, rho, _) = xe::default();
+    let options = xe::Options {
+        pressure: PressureSolver::Spectral,
+        pressure_bc: xe::PressureBc::ZeroMean,
+        ..Default::default()
+    };
+    xe::steady(
+        &mut u, &mut v, &mut p, dx, dy, 0.01, rho, 0.02, 1e-3, 20_000, &options,
+    )
+    .unwrap();
+    let post = post::post(&u, &v, dx, dy, false);
+
+    // The integrated and the Poisson streamfunctions find the same primary vortex
+    let poisson = vorticity::streamfunction(&u, &v, dx, dy);
+    let (x, y, psi) = vorticity::centre(&post.streamfunction, dx, dy, true, |_, _| true);
+    let (xp, yp, psip) = vorticity::centre(&poisson, dx, dy, true, |_, _| true);
+    assert!((x - xp).abs() < 1.5 * dx && (y - yp).abs() < 1.5 * dy);
+    assert!((psi / psip - 1.).abs() < 0.05);
+    // Rotation dominates at its centre
+    let (j, i) = ((y / dy).round() as usize, (x / dx).round() as usize);
+    assert!(post.q[[j, i]] > 0.);
+    assert!(post.vorticity[[j, i]] < 0.);
+    assert!(post.kinetic_energy > 0. && post.enstrophy > post.kinetic_energy);
+}
+
+#[test]
+fn channel() {
+    let (mut u, mut v, mut p, nit, dx, dy, dt, rho, nu, f, eps) = xf::default();
+    xf::solve(&mut u, &mut v, &mut p, nit, dx, dy, dt, rho, nu, f, eps);
+    let post = post::post(&u, &v, dx, dy, true);
+
+    // The flux at the top wall is the same through every section
+    let top = post.streamfunction.row(u.nrows() - 1);
+    let mean = top.mean().unwrap();
+    assert!(mean > 0.);
+    assert!(top.iter().all(|psi| (psi / mean - 1.).abs() < 1e-2));
+    assert!((&post.magnitude - &u.mapv(f64::abs))
+        .iter()
+        .all(|e| e.abs() < 0.1));
+}
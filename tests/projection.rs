@@ -0,0 +1,79 @@
+use cfd_rust::benchmark;
+use cfd_rust::lesson::{xe, xf};
+use cfd_rust::projection::{self, Options, Projection};
+use ndarray::prelude::*;
+
+const PROJECTIONS: [Projection; 2] = [Projection::Chorin, Projection::Incremental];
+
+#[test]
+fn cavity() {
+    let (u0, v0, p0, _, nit, dx, dy, dt, rho, nu) = xe::default();
+    let nt = 50;
+    let (mut u, mut v, mut p) = (u0.clone(), v0.clone(), p0.clone());
+    xe::solve(&mut u, &mut v, &mut p, nt, nit, dx, dy, dt, rho, nu);
+    let lesson = projection::divergence(&u, &v, dx, dy, false);
+    assert!(lesson.iter().any(|x| x.abs() > 1.));
+
+    for projection in PROJECTIONS {
+        let (mut u, mut v, mut p) = (u0.clone(), v0.clone(), p0.clone());
+        let options = Options {
+            projection,
+            ..Default::default()
+        };
+        let divergence =
+            xe::solve_projection(&mut u, &mut v, &mut p, nt, dx, dy, dt, rho, nu, &options);
+        assert_eq!(divergence.len(), nt);
+        assert!(divergence.iter().all(|d| d.linf < 1e-10));
+        assert!(u.iter().chain(v.iter()).all(|x| x.abs() <= 1.));
+    }
+}
+
+#[test]
+fn channel() {
+    // The lesson's channel on 21 x 21 nodes
+    let (_, _, _, _, _, _, dt, rho, nu, f, _) = xf::default();
+    let n = 21;
+    let (dx, dy) = (2. / (n - 1) as f64, 2. / (n - 1) as f64);
+    let (u0, v0, p0) = (
+        Array::zeros((n, n)),
+        Array::zeros((n, n)),
+        Array::ones((n, n)),
+    );
+    let exact = Array::from_shape_fn(u0.raw_dim(), |(j, _)| {
+        let y = j as f64 * dy;
+        f * y * (2. - y) / (2. * nu)
+    });
+    for projection in PROJECTIONS {
+        let (mut u, mut v, mut p) = (u0.clone(), v0.clone(), p0.clone());
+        let options = Options {
+            projection,
+            ..Default::default()
+        };
+        let divergence = xf::solve_projection(
+            &mut u, &mut v, &mut p, dx, dy, dt, rho, nu, f, 1e-8, 100_000, &options,
+        )
+        .unwrap();
+        assert!(divergence.iter().all(|d| d.linf < 1e-10));
+        assert!((&u - &exact).iter().all(|x| x.abs() < 1e-4));
+    }
+}
+
+#[test]
+fn ghia() {
+    let (mut u, mut v, mut p, _, _, dx, dy, _, rho, _) = xe::default();
+    let nu = benchmark::cavity_nu(100);
+    xe::solve_projection(
+        &mut u,
+        &mut v,
+        &mut p,
+        1500,
+        dx,
+        dy,
+        0.01,
+        rho,
+        nu,
+        &Default::default(),
+    );
+    let (eu, ev) = benchmark::cavity(&u, &v, 100).unwrap();
+    assert!(eu.linf < 0.03 && ev.linf < 0.03);
+}
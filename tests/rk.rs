@@ -0,0 +1,142 @@
+use cfd_rust::lesson::{x1, x2, x3, x4, x5, x7, x8, x9, xa};
+use cfd_rust::rk::{self, Integrator};
+use cfd_rust::tvd::{self, total_variation, Limiter};
+use cfd_rust::upwind::Upwinding;
+use ndarray::prelude::*;
+
+const INTEGRATORS: [Integrator; 5] = [
+    Integrator::Euler,
+    Integrator::Heun,
+    Integrator::SspRk3,
+    Integrator::Rk4,
+    Integrator::LowStorage,
+];
+
+fn close<D: Dimension>(a: &Array<f64, D>, b: &Array<f64, D>) -> bool {
+    return (a - b).iter().all(|x| x.abs() < 1e-12);
+}
+
+#[test]
+fn orders() {
+    // u' = -(u - sin t) + cos t with u(0) = 1, solved by u = exp(-t) + sin t
+    let l = |t: f64, u: &Array1<f64>| u.mapv(|u| -(u - t.sin()) + t.cos());
+    for integrator in INTEGRATORS {
+        let error = |nt: usize| {
+            let mut u = Array::ones(1);
+            rk::march(&mut u, nt, 1. / nt as f64, integrator, l);
+            return (u[0] - (-1f64).exp() - 1f64.sin()).abs();
+        };
+        let order = (error(20) / error(40)).log2();
+        assert!((order - integrator.order() as f64).abs() < 0.15);
+    }
+}
+
+#[test]
+fn euler() {
+    // Forward Euler on the lessons' right-hand sides reproduces their own updates
+    let (u0, nt, dx, dt, c) = x1::default();
+    let (mut a, mut b) = (u0.clone(), u0);
+    x1::solve(&mut a, nt, dx, dt, c);
+    x1::solve_rk(&mut b, nt, dx, dt, c, Integrator::Euler);
+    assert!(close(&a, &b));
+
+    let (u0, nt, dx, dt) = x2::default();
+    let (mut a, mut b) = (u0.clone(), u0);
+    x2::solve(&mut a, nt, dx, dt);
+    x2::solve_rk(&mut b, nt, dx, dt, Integrator::Euler);
+    assert!(close(&a, &b));
+
+    let (u0, nt, dx, dt, c) = x3::default();
+    let (mut a, mut b) = (u0.clone(), u0);
+    x3::solve(&mut a, nt, dx, dt, c);
+    x3::solve_rk(&mut b, nt, dx, dt, c, Integrator::Euler);
+    assert!(close(&a, &b));
+
+    let (u0, nt, dx, dt, nu) = x4::default();
+    let (mut a, mut b) = (u0.clone(), u0);
+    x4::solve(&mut a, nt, dx, dt, nu);
+    x4::solve_rk(&mut b, nt, dx, dt, nu, Integrator::Euler);
+    assert!(close(&a, &b));
+
+    let (u0, nt, dx, dt, nu) = x5::default();
+    let (mut a, mut b) = (u0.clone(), u0);
+    x5::solve(&mut a, nt, dx, dt, nu);
+    x5::solve_rk(&mut b, nt, dx, dt, nu, Integrator::Euler);
+    assert!(close(&a, &b));
+
+    let (u0, nt, dx, dy, dt, c) = x7::default();
+    let (mut a, mut b) = (u0.clone(), u0);
+    x7::solve(&mut a, nt, dx, dy, dt, c);
+    x7::solve_rk(&mut b, nt, dx, dy, dt, c, Integrator::Euler);
+    assert!(close(&a, &b));
+
+    let (u0, v0, nt, dx, dy, dt) = x8::default();
+    let (mut a, mut b, mut c, mut d) = (u0.clone(), v0.clone(), u0, v0);
+    x8::solve(&mut a, &mut b, nt, dx, dy, dt);
+    x8::solve_rk(
+        &mut c,
+        &mut d,
+        nt,
+        dx,
+        dy,
+        dt,
+        Upwinding::Backward,
+        Integrator::Euler,
+    );
+    assert!(close(&a, &c) && close(&b, &d));
+
+    let (u0, nt, dx, dy, dt, nu) = x9::default();
+    let (mut a, mut b) = (u0.clone(), u0);
+    x9::solve(&mut a, nt, dx, dy, dt, nu);
+    x9::solve_rk(&mut b, nt, dx, dy, dt, nu, Integrator::Euler);
+    assert!(close(&a, &b));
+
+    let (u0, v0, nt, dx, dy, dt, nu) = xa::default();
+    let (mut a, mut b, mut c, mut d) = (u0.clone(), v0.clone(), u0, v0);
+    xa::solve(&mut a, &mut b, nt, dx, dy, dt, nu);
+    xa::solve_rk(
+        &mut c,
+        &mut d,
+        nt,
+        dx,
+        dy,
+        dt,
+        nu,
+        Upwinding::Backward,
+        Integrator::Euler,
+    );
+    assert!(close(&a, &c) && close(&b, &d));
+}
+
+#[test]
+fn ssp() {
+    // MUSCL stays TVD under the SSP integrators at the forward Euler limit c dt / dx = 1/2
+    let (u0, nt, dx, dt, c) = x1::default();
+    for integrator in [Integrator::Euler, Integrator::Heun, Integrator::SspRk3] {
+        for limiter in [Limiter::Minmod, Limiter::Superbee] {
+            let mut u = u0.clone();
+            let mut tv = total_variation(&u);
+            for n in 0..nt {
+                rk::step(&mut u, n as f64 * dt, dt, integrator, |_, u| {
+                    tvd::rhs(u, dx, limiter, |u| c * u, |_| c)
+                });
+                let next = total_variation(&u);
+                assert!(next <= tv + 1e-12);
+                tv = next;
+            }
+        }
+    }
+}
+
+#[test]
+fn diffusion() {
+    // Beyond the forward Euler limit nu dt / dx^2 = 1/2, RK4 is still stable
+    let (u0, nt, dx, _, nu) = x4::default();
+    let dt = 0.6 * dx.powi(2) / nu;
+    let mut u = u0.clone();
+    x4::solve_rk(&mut u, nt, dx, dt, nu, Integrator::Rk4);
+    assert!(u.iter().all(|&x| (1. ..=2.).contains(&x)));
+    let mut u = u0;
+    x4::solve_rk(&mut u, 10 * nt, dx, dt, nu, Integrator::Euler);
+    assert!(u.iter().any(|&x| !(1. ..=2.).contains(&x)));
+}
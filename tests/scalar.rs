@@ -0,0 +1,137 @@
+use cfd_rust::channel;
+use cfd_rust::lesson::{xe, xf};
+use cfd_rust::scalar::{self, Condition, Options};
+use ndarray::prelude::*;
+
+#[test]
+fn translation() {
+    // Donor-cell at unit Courant number shifts the profile by a node per step
+    let (u, _, _, _, dx, dy, _, _, _, _, _) = xf::default();
+    let (ny, nx) = u.dim();
+    let phi0 = Array::from_shape_fn((ny, nx), |(_, i)| (i as f64 * dx).sin().powi(2));
+    let mut phi = phi0.clone();
+    let (u, v) = (Array::ones((ny, nx)), Array::zeros((ny, nx)));
+    for _ in 0..nx {
+        scalar::step(
+            &mut phi,
+            &u,
+            &v,
+            dx,
+            dy,
+            dx,
+            0.,
+            true,
+            &Options::default(),
+            None,
+        );
+    }
+    assert!((&phi - &phi0).iter().all(|e| e.abs() < 1e-12));
+}
+
+#[test]
+fn heated() {
+    // A uniform source between cold walls in the Poiseuille flow: phi = q / (2 kappa) y (H - y),
+    // which the scheme reproduces since the flow runs along the isolines
+    let (u, v, _, _, dx, dy, _, _, nu, f, _) = xf::default();
+    let u = channel::poiseuille(&u, dy, nu, f);
+    let (q, kappa, dt) = (1., 0.1, 0.005);
+    let options = Options {
+        bottom: Condition::Dirichlet(0.),
+        top: Condition::Dirichlet(0.),
+        ..Default::default()
+    };
+    let source = Array::from_elem(u.raw_dim(), q);
+    let mut phi = Array::zeros(u.raw_dim());
+    let mut diff = f64::INFINITY;
+    while diff > 1e-10 {
+        let phin = phi.clone();
+        scalar::step(
+            &mut phi,
+            &u,
+            &v,
+            dx,
+            dy,
+            dt,
+            kappa,
+            true,
+            &options,
+            Some(&source),
+        );
+        diff = (&phi - &phin).fold(0., |a: f64, x| a.max(x.abs())) / dt;
+    }
+    for ((j, _), value) in phi.indexed_iter() {
+        let y = j as f64 * dy;
+        assert!((value - q / (2. * kappa) * y * (2. - y)).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn conduction() {
+    // A hot bottom wall and an insulated lid heat the fluid at rest up to its temperature
+    let (u, v, _, _, _, dx, dy, _, _, _) = xe::default();
+    let options = Options {
+        bottom: Condition::Dirichlet(1.),
+        ..Default::default()
+    };
+    let mut phi = Array::zeros(u.raw_dim());
+    for _ in 0..20000 {
+        scalar::step(&mut phi, &u, &v, dx, dy, 0.005, 0.1, false, &options, None);
+    }
+    // The slowest mode, a quarter wave across the cavity, decays as exp(-kappa pi^2 t / 16)
+    assert!(phi.iter().all(|x| (x - 1.).abs() < 5e-3));
+}
+
+#[test]
+fn mixing() {
+    // The cavity stirs a layer of scalar through its insulated walls
+    let (mut u, mut v, mut p, nt, _, dx, dy, dt, rho, nu) = xe::default();
+    let mut phi = Array::from_shape_fn(u.raw_dim(), |(j, _)| if j < 20 { 1. } else { 0. });
+    let variance = |phi: &Array2<f64>| phi.var(0.);
+    let (mean, initial) = (phi.mean().unwrap(), variance(&phi));
+    let options = xe::Options::default();
+    let scalar = Options::default();
+    xe::solve_scalar(
+        &mut u, &mut v, &mut p, &mut phi, nt, dx, dy, dt, rho, nu, 0.01, &options, &scalar, None,
+    );
+    assert!(phi.iter().all(|x| (-1e-12..=1. + 1e-12).contains(x)));
+    assert!(variance(&phi) < 0.9 * initial);
+    assert!((phi.mean().unwrap() / mean - 1.).abs() < 1e-2);
+    // The flow is that of xe
+    let (mut ue, mut ve, mut pe, _, _, _, _, _, _, _) = xe::default();
+    xe::solve_with(&mut ue, &mut ve, &mut pe, nt, dx, dy, dt, rho, nu, &options);
+    assert_eq!(u, ue);
+}
+
+#[test]
+fn channel() {
+    // A hot bottom wall and a cold top wall
+    let (mut u, mut v, mut p, _, dx, dy, dt, rho, nu, f, _) = xf::default();
+    let options = Options {
+        bottom: Condition::Dirichlet(1.),
+        top: Condition::Dirichlet(0.),
+        ..Default::default()
+    };
+    let mut phi = Array::zeros(u.raw_dim());
+    xf::solve_scalar(
+        &mut u,
+        &mut v,
+        &mut p,
+        &mut phi,
+        3000,
+        dx,
+        dy,
+        dt,
+        rho,
+        nu,
+        f,
+        0.05,
+        &Default::default(),
+        &options,
+        None,
+    );
+    // The scalar diffuses across the isolines of the developing flow into the linear profile
+    for ((j, _), value) in phi.indexed_iter() {
+        assert!((value - (1. - j as f64 * dy / 2.)).abs() < 2e-2);
+    }
+    assert!(u.iter().all(|x| *x >= 0.));
+}
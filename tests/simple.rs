@@ -0,0 +1,84 @@
+use cfd_rust::benchmark;
+use cfd_rust::mac::Mac;
+use cfd_rust::simple::{self, Algorithm, Options};
+use ndarray::prelude::*;
+
+const ALGORITHMS: [(Algorithm, f64); 3] = [
+    (Algorithm::Simple, 0.3),
+    (Algorithm::Simplec, 1.),
+    (Algorithm::Piso(2), 1.),
+];
+
+// Poiseuille flow u = f y (2 - y) / (2 nu) at the cell centres
+#[test]
+fn channel() {
+    let (nx, ny, nu, f) = (4, 10, 0.1, 1.);
+    let (dx, dy) = (2. / nx as f64, 2. / ny as f64);
+    let exact = Array::from_shape_fn((ny, nx), |(j, _)| {
+        let y = (j as f64 + 0.5) * dy;
+        f * y * (2. - y) / (2. * nu)
+    });
+    for (algorithm, alpha_p) in ALGORITHMS {
+        let options = Options {
+            algorithm,
+            alpha_p,
+            ..Default::default()
+        };
+        let mut m = Mac::new(nx, ny, dx, dy);
+        let residuals = simple::channel(&mut m, 1., nu, f, 5000, 1e-8, &options);
+        assert!(residuals.last().unwrap().linf < 1e-8);
+        let (u, v) = m.centres();
+        assert!((u - &exact).iter().all(|e| e.abs() < 2. * dy.powi(2)));
+        assert!(v.iter().all(|x| x.abs() < 1e-6));
+    }
+}
+
+#[test]
+fn ghia() {
+    let (n, nu) = (40, benchmark::cavity_nu(100));
+    let h = 2. / n as f64;
+    let mut m = Mac::new(n, n, h, h);
+    let residuals = simple::cavity(&mut m, 1., nu, 1., 2000, 1e-6, &Default::default());
+    assert!(residuals.last().unwrap().linf < 1e-6);
+    // Straight to the steady state, in fewer iterations than the time steps of `mac`
+    assert!(residuals.len() < 1000);
+    let (u, v, _) = m.nodes();
+    let (eu, ev) = benchmark::cavity(&u, &v, 100).unwrap();
+    assert!(eu.linf < 0.01 && ev.linf < 0.01);
+}
+
+#[test]
+fn relaxation() {
+    let (n, nu) = (10, benchmark::cavity_nu(100));
+    let h = 2. / n as f64;
+    let run = |algorithm, alpha_p, iterations| {
+        let options = Options {
+            algorithm,
+            alpha_p,
+            ..Default::default()
+        };
+        let mut m = Mac::new(n, n, h, h);
+        let residuals = simple::cavity(&mut m, 1., nu, 1., iterations, 1e-6, &options);
+        return (m, residuals);
+    };
+    // SIMPLE overestimates the pressure correction without under-relaxation, and the
+    // imbalance grows without bound
+    let (_, residuals) = run(Algorithm::Simple, 1., 100);
+    assert!(residuals[99].l2 > 1e6 * residuals[0].l2);
+
+    let (reference, _) = run(Algorithm::Simple, 0.3, 2000);
+    for (algorithm, alpha_p) in ALGORITHMS {
+        let (m, residuals) = run(algorithm, alpha_p, 2000);
+        assert!(residuals.last().unwrap().linf < 1e-6);
+        assert!(m.divergence().iter().all(|d| d.abs() < 1e-6));
+        // The same steady state whatever the coupling
+        assert!((&m.u - &reference.u).iter().all(|e| e.abs() < 1e-4));
+    }
+
+    // The second PISO corrector takes up in full whatever the relaxed first one left, so
+    // alpha_p drops out
+    let (relaxed, a) = run(Algorithm::Piso(2), 0.3, 2000);
+    let (full, b) = run(Algorithm::Piso(2), 1., 2000);
+    assert_eq!(a.len(), b.len());
+    assert!((&relaxed.u - &full.u).iter().all(|e| e.abs() < 1e-8));
+}
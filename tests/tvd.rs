@@ -0,0 +1,124 @@
+use cfd_rust::advection::Scheme;
+use cfd_rust::analytic::{self, advected_hat};
+use cfd_rust::lesson::{x1, x2, x7, x8};
+use cfd_rust::mms;
+use cfd_rust::norm::norms;
+use cfd_rust::tvd::{self, total_variation, Limiter};
+use cfd_rust::upwind::Upwinding;
+use ndarray::prelude::*;
+
+const LIMITERS: [Limiter; 4] = [
+    Limiter::Minmod,
+    Limiter::Superbee,
+    Limiter::VanLeer,
+    Limiter::MC,
+];
+
+fn bounded<S: ndarray::Data<Elem = f64>, D: Dimension>(u: &ArrayBase<S, D>) -> bool {
+    return u.iter().all(|&x| (1. - 1e-12..=2. + 1e-12).contains(&x));
+}
+
+#[test]
+fn sweby() {
+    // Second order through psi(1) = 1, inside the TVD region 0 <= psi <= min(2 r, 2)
+    for limiter in LIMITERS {
+        assert!((limiter.psi(1.) - 1.).abs() < 1e-15);
+        for k in -40..=80 {
+            let r = k as f64 / 10.;
+            let psi = limiter.psi(r);
+            assert!(psi >= 0. && psi <= (2. * r).clamp(0., 2.) + 1e-15);
+        }
+    }
+}
+
+#[test]
+fn square_wave() {
+    let (u0, nt, dx, dt, c) = x1::default();
+    let error = |u: &Array1<f64>| {
+        return analytic::errors(u, dx, nt as f64 * dt, |x, t| advected_hat(x, t, c)).l1;
+    };
+    let mut upwind = u0.clone();
+    x1::solve(&mut upwind, nt, dx, dt, c);
+    let mut minmod = f64::INFINITY;
+    for limiter in LIMITERS {
+        let mut u = u0.clone();
+        let mut tv = total_variation(&u);
+        for _ in 0..nt {
+            x1::solve_with(&mut u, 1, dx, dt, c, Scheme::Muscl(limiter));
+            let next = total_variation(&u);
+            assert!(next <= tv + 1e-12);
+            tv = next;
+        }
+        assert!(bounded(&u));
+        assert!(error(&u) < 0.5 * error(&upwind));
+        if limiter == Limiter::Minmod {
+            minmod = error(&u);
+        } else {
+            // Minmod is the most diffusive of the four
+            assert!(error(&u) < minmod);
+        }
+    }
+
+    // Lax-Wendroff oscillates and its total variation grows
+    let mut u = u0.clone();
+    x1::solve_with(&mut u, nt, dx, dt, c, Scheme::LaxWendroff);
+    assert!(!bounded(&u) && total_variation(&u) > 1.2 * total_variation(&u0));
+}
+
+#[test]
+fn burgers() {
+    // Halve the lesson's time step to keep max(u) dt / dx <= 1/2
+    let (u0, nt, dx, dt) = x2::default();
+    let (nt, dt) = (2 * nt, dt / 2.);
+    for limiter in LIMITERS {
+        let mut u = u0.clone();
+        let mut tv = total_variation(&u);
+        for _ in 0..nt {
+            x2::solve_with(&mut u, 1, dx, dt, Scheme::Muscl(limiter));
+            let next = total_variation(&u);
+            assert!(next <= tv + 1e-12);
+            tv = next;
+        }
+        assert!(bounded(&u));
+        let e = analytic::errors(&u, dx, nt as f64 * dt, analytic::burgers_hat);
+        assert!(e.l1 < 0.08);
+    }
+}
+
+#[test]
+fn linear2() {
+    // Total variation is not bounded in 2D, but the maximum principle still holds
+    let (u0, nt, dx, dy, dt, c) = x7::default();
+    let t = (nt + 1) as f64 * dt;
+    let exact = mms::sample(
+        |t, x, y| 1. + (advected_hat(x, t, c) - 1.) * (advected_hat(y, t, c) - 1.),
+        t,
+        u0.dim(),
+        dx,
+        dy,
+    );
+    let mut upwind = u0.clone();
+    x7::solve(&mut upwind, nt, dx, dy, dt, c);
+    for limiter in LIMITERS {
+        let mut u = u0.clone();
+        x7::solve_tvd(&mut u, nt, dx, dy, dt, c, limiter);
+        assert!(bounded(&u));
+        assert!(norms(&(&u - &exact)).l1 < 0.7 * norms(&(&upwind - &exact)).l1);
+    }
+}
+
+#[test]
+fn burgers2() {
+    // Halve the lesson's time step to keep (|u| / dx + |v| / dy) dt <= 1/2
+    let (u0, v0, nt, dx, dy, dt) = x8::default();
+    let (nt, dt) = (2 * nt, dt / 2.);
+    for limiter in LIMITERS {
+        let mut w = u0.clone();
+        tvd::burgers2(&mut w, nt + 1, dx, dy, dt, limiter);
+        assert!(bounded(&w));
+
+        let (mut u, mut v) = (u0.clone(), v0.clone());
+        x8::solve_with(&mut u, &mut v, nt, dx, dy, dt, Upwinding::Muscl(limiter));
+        assert!(bounded(&u) && bounded(&v));
+    }
+}
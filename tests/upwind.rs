@@ -0,0 +1,111 @@
+use cfd_rust::benchmark;
+use cfd_rust::convergence::{self, Study};
+use cfd_rust::lesson::{x8, xe};
+use cfd_rust::norm::norms;
+use cfd_rust::poisson::PressureSolver;
+use cfd_rust::upwind::{convection, Upwinding};
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+// Error of the convective term of a smooth field in a flow of both signs, on n x n nodes
+// spanning [0, 2]^2, or [0, 2) in x when periodic
+fn study(upwinding: Upwinding, periodic: bool) -> Study {
+    return convergence::exact(&convergence::grids(21, 2, 4), |n| {
+        let dx = if periodic {
+            2. / n as f64
+        } else {
+            2. / (n - 1) as f64
+        };
+        let dy = 2. / (n - 1) as f64;
+        let sample = |f: &dyn Fn(f64, f64) -> f64| {
+            Array::from_shape_fn((n, n), |(j, i)| f(i as f64 * dx, j as f64 * dy))
+        };
+        let phi = sample(&|x, y| (PI * x).sin() * (PI * y / 2.).cos());
+        let u = sample(&|x, y| (PI * x).cos() + 0.3 * y);
+        let v = sample(&|x, y| (PI * y).sin() - 0.2 * x);
+        let exact = sample(&|x, y| {
+            ((PI * x).cos() + 0.3 * y) * PI * (PI * x).cos() * (PI * y / 2.).cos()
+                - ((PI * y).sin() - 0.2 * x) * PI / 2. * (PI * x).sin() * (PI * y / 2.).sin()
+        });
+        let c = convection(&phi, &u, &v, dx, dy, upwinding, periodic);
+        let columns = if periodic {
+            s![1..-1, ..]
+        } else {
+            s![1..-1, 1..-1]
+        };
+        (dy, norms(&(&c.slice(columns) - &exact.slice(columns))))
+    });
+}
+
+#[test]
+fn orders() {
+    for (upwinding, order) in [
+        (Upwinding::DonorCell, 1.),
+        (Upwinding::SecondOrder, 2.),
+        (Upwinding::Quick, 2.),
+    ] {
+        for periodic in [false, true] {
+            let study = study(upwinding, periodic);
+            assert!((study.order(|e| e.l1) - order).abs() < 0.15);
+        }
+    }
+}
+
+#[test]
+fn negative_velocity() {
+    // The backward differences of the lesson are downwind here
+    let (u, v, nt, dx, dy, dt) = x8::default();
+    let (mut a, mut b) = (-&u, -&v);
+    x8::solve_with(&mut a, &mut b, nt, dx, dy, dt, Upwinding::Backward);
+    assert!(a.iter().any(|x| !x.is_finite() || x.abs() > 1e3));
+
+    // Donor-cell stays within the bounds of the data
+    let (mut a, mut b) = (-&u, -&v);
+    x8::solve_with(&mut a, &mut b, nt, dx, dy, dt, Upwinding::DonorCell);
+    assert!(a.iter().chain(b.iter()).all(|&x| (-2. ..=1.).contains(&x)));
+}
+
+#[test]
+fn positive_velocity() {
+    // Donor-cell and the lesson agree when the flow is positive everywhere
+    let (u, v, nt, dx, dy, dt) = x8::default();
+    let (mut a, mut b) = (u.clone(), v.clone());
+    x8::solve(&mut a, &mut b, nt, dx, dy, dt);
+    let (mut c, mut d) = (u, v);
+    x8::solve_with(&mut c, &mut d, nt, dx, dy, dt, Upwinding::DonorCell);
+    assert_eq!(a, c);
+    assert_eq!(b, d);
+
+    // Also across the first column when it wraps around
+    let field = |f: fn(usize, usize) -> f64| Array::from_shape_fn((11, 10), |(j, i)| f(j, i));
+    let phi = field(|j, i| ((i * i + 3 * j) % 7) as f64);
+    let (u, v) = (
+        field(|j, i| 1. + (i + 2 * j) as f64 / 10.),
+        field(|j, _| 2. - j as f64 / 10.),
+    );
+    for periodic in [false, true] {
+        assert_eq!(
+            convection(&phi, &u, &v, dx, dy, Upwinding::Backward, periodic),
+            convection(&phi, &u, &v, dx, dy, Upwinding::DonorCell, periodic)
+        );
+    }
+}
+
+#[test]
+fn ghia() {
+    let error = |convection| {
+        let options = xe::Options {
+            pressure: PressureSolver::Spectral,
+            pressure_bc: xe::PressureBc::ZeroMean,
+            convection,
+            ..Default::default()
+        };
+        let (u, v, _, _, _) = benchmark::xe_cavity(100, &options).unwrap();
+        let (eu, ev) = benchmark::cavity(&u, &v, 100).unwrap();
+        return eu.l2 + ev.l2;
+    };
+    let donor = error(Upwinding::DonorCell);
+    assert!(donor < 0.05);
+    assert!(error(Upwinding::SecondOrder) < 0.7 * donor);
+    assert!(error(Upwinding::Quick) < donor);
+}
@@ -0,0 +1,89 @@
+use cfd_rust::benchmark;
+use cfd_rust::lesson::xe;
+use cfd_rust::poisson::PressureSolver;
+use cfd_rust::upwind::Upwinding;
+use cfd_rust::vorticity::{self, Options};
+use ndarray::prelude::*;
+
+fn steady(poisson: PressureSolver) -> (Array2<f64>, Array2<f64>, f64, f64) {
+    let (u, _, _, _, _, dx, dy, _, _, _) = xe::default();
+    let mut omega = Array::zeros(u.raw_dim());
+    let mut psi = Array::zeros(u.raw_dim());
+    let options = Options {
+        poisson,
+        convection: Upwinding::SecondOrder,
+        ..Default::default()
+    };
+    let nu = benchmark::cavity_nu(100);
+    vorticity::steady(
+        &mut omega, &mut psi, dx, dy, 0.01, nu, 1e-3, 20_000, &options,
+    )
+    .unwrap();
+    return (omega, psi, dx, dy);
+}
+
+#[test]
+fn ghia() {
+    let (omega, psi, dx, dy) = steady(PressureSolver::Spectral);
+    assert!(omega.iter().all(|x| x.is_finite()));
+    let (u, v) = vorticity::velocity(&psi, dx, dy, 1.);
+    let (eu, ev) = benchmark::cavity(&u, &v, 100).unwrap();
+    assert!(eu.linf < 0.015 && ev.linf < 0.015);
+
+    // Ghia et al. (1982), Table III/IV, scaled to the 2 x 2 cavity: psi scales with U L
+    let (x, y, value) = vorticity::centre(&psi, dx, dy, true, |_, _| true);
+    assert!((x - 2. * 0.6172).abs() <= dx && (y - 2. * 0.7344).abs() <= dy);
+    assert!((value / (2. * -0.1034) - 1.).abs() < 0.02);
+    // The bottom-right secondary vortex turns the other way
+    let (x, y, value) = vorticity::centre(&psi, dx, dy, false, |x, y| x > 1.5 && y < 0.5);
+    assert!((x - 2. * 0.9453).abs() <= dx && (y - 2. * 0.0625).abs() <= dy);
+    assert!(value > 0.);
+}
+
+#[test]
+fn jacobi() {
+    // The Jacobi sweeps of xd, warm-started from the last streamfunction
+    let (_, spectral, _, _) = steady(PressureSolver::Spectral);
+    let (_, jacobi, _, _) = steady(PressureSolver::Jacobi(50));
+    assert!((jacobi - spectral).iter().all(|e| e.abs() < 1e-3));
+}
+
+#[test]
+fn primitive() {
+    let options = xe::Options {
+        pressure: PressureSolver::Spectral,
+        pressure_bc: xe::PressureBc::ZeroMean,
+        ..Default::default()
+    };
+    let (u, v, _, dx, dy) = benchmark::xe_cavity(100, &options).unwrap();
+    let psi = vorticity::streamfunction(&u, &v, dx, dy);
+
+    let (_, reference, _, _) = steady(PressureSolver::Spectral);
+    let (x, y, value) = vorticity::centre(&psi, dx, dy, true, |_, _| true);
+    let (xr, yr, vr) = vorticity::centre(&reference, dx, dy, true, |_, _| true);
+    assert!((x - xr).abs() <= dx && (y - yr).abs() <= dy);
+    assert!((value / vr - 1.).abs() < 0.05);
+}
+
+#[test]
+fn steady_failures() {
+    let (u, _, _, _, _, dx, dy, _, _, _) = xe::default();
+    let run = |dt, max_steps| {
+        let mut omega = Array::zeros(u.raw_dim());
+        let mut psi = Array::zeros(u.raw_dim());
+        let options = Options::default();
+        return vorticity::steady(
+            &mut omega, &mut psi, dx, dy, dt, 0.02, 1e-3, max_steps, &options,
+        );
+    };
+    assert_eq!(run(0.01, 10), None);
+    assert_eq!(run(1., 10_000), None);
+}
+
+#[test]
+#[should_panic(expected = "at least one Jacobi sweep")]
+fn no_sweeps() {
+    let (u, _, _, _, _, dx, dy, _, _, _) = xe::default();
+    let mut psi = Array::zeros(u.raw_dim());
+    vorticity::streamfunction_of(&mut psi, &u, dx, dy, PressureSolver::Jacobi(0));
+}
@@ -0,0 +1,90 @@
+use cfd_rust::analytic;
+use cfd_rust::convergence;
+use cfd_rust::lesson::{x2, x5, xa};
+use cfd_rust::norm::norms;
+use cfd_rust::upwind::Upwinding;
+use cfd_rust::weno::{godunov, reconstruct, Splitting, Weno};
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+const SCHEMES: [(Weno, Splitting); 4] = [
+    (Weno::JS, Splitting::LaxFriedrichs),
+    (Weno::JS, Splitting::Godunov),
+    (Weno::Z, Splitting::LaxFriedrichs),
+    (Weno::Z, Splitting::Godunov),
+];
+
+#[test]
+fn fifth_order() {
+    // Differences of the reconstructed faces approximate f' on a periodic grid
+    let f = |x: f64| x.sin() + 0.3 * (2. * x).cos();
+    let df = |x: f64| x.cos() - 0.6 * (2. * x).sin();
+    for weno in [Weno::JS, Weno::Z] {
+        let study = convergence::exact(&convergence::grids(21, 2, 3), |n| {
+            let h = 2. * PI / (n - 1) as f64;
+            let face =
+                |i: isize| reconstruct([-2, -1, 0, 1, 2].map(|o| f((i + o) as f64 * h)), weno);
+            let e = Array::from_shape_fn(n - 1, |i| {
+                (face(i as isize) - face(i as isize - 1)) / h - df(i as f64 * h)
+            });
+            (h, norms(&e))
+        });
+        assert!(study.order(|e| e.linf) > 4.5);
+    }
+}
+
+#[test]
+fn riemann() {
+    assert_eq!(godunov(2., 1.), 2.);
+    assert_eq!(godunov(-1., -2.), 2.);
+    assert_eq!(godunov(-1., 2.), 0.);
+    assert_eq!(godunov(1., 2.), 0.5);
+}
+
+#[test]
+fn shock_speed() {
+    // The hat of x2 at t = 0.5: its front is a shock moving at (2 + 1) / 2 = 1.5, starting
+    // halfway between the last node at 2 and the first one at 1
+    let (u0, _, dx, _) = x2::default();
+    let dt = 0.2 * dx;
+    let nt = (0.5 / dt).round() as usize;
+    let t = nt as f64 * dt;
+    for (weno, splitting) in SCHEMES {
+        let mut u = u0.clone();
+        x2::solve_weno(&mut u, nt, dx, dt, weno, splitting);
+        let k = (0..u.len() - 1).rev().find(|&k| u[k] >= 1.5).unwrap();
+        let front = (k as f64 + (u[k] - 1.5) / (u[k] - u[k + 1])) * dx;
+        assert!((front - (1. + dx / 2. + 1.5 * t)).abs() < 0.1 * dx);
+        assert!(u.iter().all(|&x| (1. - 1e-3..=2. + 1e-3).contains(&x)));
+        let e = analytic::errors(&u, dx, t, analytic::burgers_hat);
+        assert!(e.l1 < 0.04);
+    }
+}
+
+#[test]
+fn x5() {
+    let (u0, nt, dx, dt, nu) = x5::default();
+    let t = nt as f64 * dt;
+    let error = |u: &Array1<f64>| {
+        return analytic::errors(u, dx, t, |x, t| analytic::burgers(x, t, nu)).l1;
+    };
+    let mut lesson = u0.clone();
+    x5::solve(&mut lesson, nt, dx, dt, nu);
+    for (weno, splitting) in SCHEMES {
+        let mut u = u0.clone();
+        x5::solve_weno(&mut u, nt, dx, dt, nu, weno, splitting);
+        assert!(error(&u) < 0.1 * error(&lesson));
+    }
+}
+
+#[test]
+fn xa() {
+    for weno in [Weno::JS, Weno::Z] {
+        let (mut u, mut v, nt, dx, dy, dt, nu) = xa::default();
+        xa::solve_with(&mut u, &mut v, nt, dx, dy, dt, nu, Upwinding::Weno5(weno));
+        assert!(u
+            .iter()
+            .chain(v.iter())
+            .all(|&x| (1. - 1e-3..=2. + 1e-3).contains(&x)));
+    }
+}